@@ -1,23 +1,10 @@
-use pool_tree::tree::Tree;
+use pool_tree::tree;
 
 fn main() {
-    let mut tree = Tree::new(7);
-
-    let mut node = tree.mut_top();
-
-    node.add_child(0);
-    node.add_child(2);
-    node.add_child(4);
-    node.add_child(6);
-
-    let mut node = node.get_child_unique(2);
-
-    node.add_child(4);
-    node.add_child(4);
-    node.add_child(4);
-    node.add_child(4);
+    let tree = tree![7 => [0, 2, 4 => [4, 4, 4, 4], 6]];
 
     println!("tree: {:#?}", tree);
     println!("tree: {}", tree);
+    println!("pool: {:#?}", tree.debug_pool());
 
-}
\ No newline at end of file
+}
@@ -0,0 +1,265 @@
+use std::fmt::{Display, Formatter};
+use std::error::Error;
+use std::io;
+use crate::tree::Tree;
+use crate::reference::TreeRef;
+
+/// the first bytes of every encoded Tree
+pub const MAGIC: [u8; 4] = *b"PTRE";
+
+/// the version of the format written by [`Tree::to_bytes`]
+pub const VERSION: u16 = 1;
+
+/// Encodes and decodes values for the binary format of Tree
+pub trait BinaryCodec: Sized {
+    /// appends the encoded value to out
+    fn encode(&self, out: &mut Vec<u8>);
+
+    /// decodes a value from the start of input and advances input behind it
+    fn decode(input: &mut &[u8]) -> Result<Self, BinaryError>;
+}
+
+/// The error returned when decoding a Tree
+#[derive(Debug)]
+pub enum BinaryError {
+    /// the data does not start with [`MAGIC`]
+    InvalidMagic,
+    UnsupportedVersion(u16),
+    UnexpectedEnd,
+    /// the child counts do not describe a single tree with the given number of nodes
+    InvalidShape,
+    ChecksumMismatch,
+    /// the data continues after the checksum
+    TrailingData,
+    /// a value could not be decoded
+    InvalidValue(&'static str),
+    Io(io::Error),
+}
+
+impl Display for BinaryError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BinaryError::InvalidMagic => f.write_str("the data is no encoded tree"),
+            BinaryError::UnsupportedVersion(version) => write!(f, "unsupported version {}", version),
+            BinaryError::UnexpectedEnd => f.write_str("unexpected end of data"),
+            BinaryError::InvalidShape => f.write_str("invalid tree shape"),
+            BinaryError::ChecksumMismatch => f.write_str("checksum mismatch"),
+            BinaryError::TrailingData => f.write_str("data after the checksum"),
+            BinaryError::InvalidValue(reason) => write!(f, "invalid value: {}", reason),
+            BinaryError::Io(error) => error.fmt(f),
+        }
+    }
+}
+
+impl Error for BinaryError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            BinaryError::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for BinaryError {
+    fn from(error: io::Error) -> Self {
+        BinaryError::Io(error)
+    }
+}
+
+impl<T: BinaryCodec + 'static> Tree<T> {
+    /// encodes the Tree. The format consists of [`MAGIC`], the version and the number of nodes,
+    /// followed by the child counts of all nodes in pre-order as LEB128 varints, the values in
+    /// pre-order and a CRC-32 of all previous bytes. Integers are little endian.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        out.extend_from_slice(&MAGIC);
+        out.extend_from_slice(&VERSION.to_le_bytes());
+        let count_position = out.len();
+        out.extend_from_slice(&[0; 4]);
+
+        let mut count: u32 = 0;
+        for node in self.top().pre_order() {
+            write_varint(&mut out, node.children_count());
+            count += 1;
+        }
+        out[count_position..count_position + 4].copy_from_slice(&count.to_le_bytes());
+        for node in self.top().pre_order() {
+            (*node).encode(&mut out);
+        }
+
+        let checksum = crc32(&out);
+        out.extend_from_slice(&checksum.to_le_bytes());
+        out
+    }
+
+    /// writes the encoding of [`Tree::to_bytes`] to out
+    pub fn write_binary(&self, out: &mut impl io::Write) -> io::Result<()> {
+        out.write_all(&self.to_bytes())
+    }
+
+    /// decodes a Tree encoded by [`Tree::to_bytes`]. The buffer of the Tree is allocated once with
+    /// the exact number of nodes, which are stored in pre-order.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, BinaryError> {
+        if bytes.len() < MAGIC.len() || bytes[..MAGIC.len()] != MAGIC {
+            return Err(BinaryError::InvalidMagic);
+        }
+        let mut input = &bytes[MAGIC.len()..];
+        let version = u16::decode(&mut input)?;
+        if version != VERSION {
+            return Err(BinaryError::UnsupportedVersion(version));
+        }
+        if input.len() < 4 {
+            return Err(BinaryError::UnexpectedEnd);
+        }
+        let (data, checksum) = bytes.split_at(bytes.len() - 4);
+        if crc32(data) != u32::from_le_bytes([checksum[0], checksum[1], checksum[2], checksum[3]]) {
+            return Err(BinaryError::ChecksumMismatch);
+        }
+        let mut input = &data[MAGIC.len() + 2..];
+
+        let count = u32::decode(&mut input)? as usize;
+        // every node needs at least one byte, this prevents huge allocations for corrupt counts
+        if count == 0 || count > input.len() {
+            return Err(BinaryError::InvalidShape);
+        }
+        let mut child_counts = Vec::with_capacity(count);
+        let mut open: u64 = 1;
+        for _ in 0..count {
+            let children = read_varint(&mut input)?;
+            if open == 0 {
+                return Err(BinaryError::InvalidShape);
+            }
+            open = open - 1 + children as u64;
+            child_counts.push(children);
+        }
+        if open != 0 {
+            return Err(BinaryError::InvalidShape);
+        }
+
+        let mut tree = Tree::with_capacity(T::decode(&mut input)?, count);
+        // the nodes which still miss children and the number of missing children
+        let mut stack = vec![(0, child_counts[0])];
+        for children in child_counts.into_iter().skip(1) {
+            while stack.last().is_some_and(|(_, missing)|*missing == 0) {
+                stack.pop();
+            }
+            let (parent, missing) = stack.last_mut().ok_or(BinaryError::InvalidShape)?;
+            *missing -= 1;
+            let index = unsafe {tree.push_child(*parent, T::decode(&mut input)?)};
+            stack.push((index.get(), children));
+        }
+        if !input.is_empty() {
+            return Err(BinaryError::TrailingData);
+        }
+        Ok(tree)
+    }
+
+    /// reads all bytes of input and decodes them with [`Tree::from_bytes`]
+    pub fn read_binary(input: &mut impl io::Read) -> Result<Self, BinaryError> {
+        let mut bytes = Vec::new();
+        input.read_to_end(&mut bytes)?;
+        Tree::from_bytes(&bytes)
+    }
+}
+
+fn write_varint(out: &mut Vec<u8>, mut value: u32) {
+    while value >= 0x80 {
+        out.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+fn read_varint(input: &mut &[u8]) -> Result<u32, BinaryError> {
+    let mut value: u32 = 0;
+    for shift in (0..32).step_by(7) {
+        let byte = take(input, 1)?[0];
+        // the fifth byte may only hold the remaining four bits of an u32
+        if shift == 28 && byte > 0x0F {
+            return Err(BinaryError::InvalidShape);
+        }
+        value |= ((byte & 0x7F) as u32) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    unreachable!()
+}
+
+/// CRC-32 as used by zlib and PNG
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
+        }
+    }
+    !crc
+}
+
+fn take<'a>(input: &mut &'a [u8], count: usize) -> Result<&'a [u8], BinaryError> {
+    if input.len() < count {
+        return Err(BinaryError::UnexpectedEnd);
+    }
+    let (bytes, rest) = input.split_at(count);
+    *input = rest;
+    Ok(bytes)
+}
+
+macro_rules! impl_codec_le {
+    ($($number: ty),*) => {
+        $(
+            impl BinaryCodec for $number {
+                fn encode(&self, out: &mut Vec<u8>) {
+                    out.extend_from_slice(&self.to_le_bytes());
+                }
+
+                fn decode(input: &mut &[u8]) -> Result<Self, BinaryError> {
+                    let mut bytes = [0; std::mem::size_of::<$number>()];
+                    let length = bytes.len();
+                    bytes.copy_from_slice(take(input, length)?);
+                    Ok(<$number>::from_le_bytes(bytes))
+                }
+            }
+        )*
+    }
+}
+
+impl_codec_le!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128, f32, f64);
+
+impl BinaryCodec for () {
+    fn encode(&self, _out: &mut Vec<u8>) {}
+
+    fn decode(_input: &mut &[u8]) -> Result<Self, BinaryError> {
+        Ok(())
+    }
+}
+
+impl BinaryCodec for bool {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.push(*self as u8);
+    }
+
+    fn decode(input: &mut &[u8]) -> Result<Self, BinaryError> {
+        match take(input, 1)?[0] {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(BinaryError::InvalidValue("bool is neither 0 nor 1")),
+        }
+    }
+}
+
+/// Strings are encoded as their length in bytes as u32 followed by the UTF-8 bytes
+impl BinaryCodec for String {
+    fn encode(&self, out: &mut Vec<u8>) {
+        (self.len() as u32).encode(out);
+        out.extend_from_slice(self.as_bytes());
+    }
+
+    fn decode(input: &mut &[u8]) -> Result<Self, BinaryError> {
+        let length = u32::decode(input)? as usize;
+        let bytes = take(input, length)?;
+        String::from_utf8(bytes.to_vec()).map_err(|_|BinaryError::InvalidValue("string is not valid UTF-8"))
+    }
+}
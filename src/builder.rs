@@ -0,0 +1,141 @@
+use std::fmt::{Display, Formatter};
+use std::error::Error;
+use crate::tree::Tree;
+
+/// Builds a Tree from a stream of events. [`TreeBuilder::open`] adds a node and makes it the
+/// parent of the following nodes until the matching [`TreeBuilder::close`], [`TreeBuilder::leaf`]
+/// adds a node without children. The first node is the root.
+///
+/// Errors are not reported by the events, the first one is returned by [`TreeBuilder::finish`]
+/// and all later events are ignored.
+pub struct TreeBuilder<T> {
+    tree: Option<Tree<T>>,
+    /// the indices of the open nodes
+    open: Vec<u32>,
+    /// the number of events so far
+    events: usize,
+    /// the capacity the Tree is created with
+    capacity: usize,
+    error: Option<BuildError>,
+}
+
+/// The error returned by [`TreeBuilder::finish`]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BuildError {
+    /// the number of the event which caused the error starting at 0, or the number of all events
+    /// if the error was detected by finish
+    pub event: usize,
+    pub kind: BuildErrorKind,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum BuildErrorKind {
+    /// no node was added
+    Empty,
+    /// finish was called while the given number of nodes were still open
+    Unclosed(usize),
+    /// close was called without an open node
+    UnexpectedClose,
+    /// a node was added after the root was closed
+    MultipleRoots,
+}
+
+impl Display for BuildError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match &self.kind {
+            BuildErrorKind::Empty => f.write_str("no node was added"),
+            BuildErrorKind::Unclosed(count) => write!(f, "{} nodes were not closed", count),
+            BuildErrorKind::UnexpectedClose => write!(f, "close without an open node at event {}", self.event),
+            BuildErrorKind::MultipleRoots => write!(f, "second root at event {}", self.event),
+        }
+    }
+}
+
+impl Error for BuildError {}
+
+impl<T: 'static> TreeBuilder<T> {
+    pub fn new() -> Self {
+        TreeBuilder {
+            tree: None,
+            open: Vec::new(),
+            events: 0,
+            capacity: 1,
+            error: None,
+        }
+    }
+
+    /// creates a builder whose Tree has space for capacity nodes
+    pub fn with_capacity(capacity: usize) -> Self {
+        TreeBuilder {
+            capacity,
+            ..TreeBuilder::new()
+        }
+    }
+
+    /// adds a node, which is the parent of the following nodes until close is called
+    pub fn open(&mut self, value: T) -> &mut Self {
+        if let Some(index) = self.add(value) {
+            self.open.push(index);
+        }
+        self
+    }
+
+    /// adds a node without children
+    pub fn leaf(&mut self, value: T) -> &mut Self {
+        self.add(value);
+        self
+    }
+
+    /// closes the last opened node
+    pub fn close(&mut self) -> &mut Self {
+        if self.error.is_none() && self.open.pop().is_none() {
+            self.fail(BuildErrorKind::UnexpectedClose);
+        }
+        self.events += 1;
+        self
+    }
+
+    /// returns the number of open nodes
+    pub fn depth(&self) -> usize {
+        self.open.len()
+    }
+
+    /// returns the Tree if all nodes were closed and no error occurred
+    pub fn finish(self) -> Result<Tree<T>, BuildError> {
+        let event = self.events;
+        match (self.error, self.tree, self.open.len()) {
+            (Some(error), _, _) => Err(error),
+            (None, None, _) => Err(BuildError{ event, kind: BuildErrorKind::Empty }),
+            (None, Some(tree), 0) => Ok(tree),
+            (None, Some(_), open) => Err(BuildError{ event, kind: BuildErrorKind::Unclosed(open) }),
+        }
+    }
+
+    /// adds a node to the last open node and returns its index
+    fn add(&mut self, value: T) -> Option<u32> {
+        let index = match (&mut self.tree, self.open.last()) {
+            _ if self.error.is_some() => None,
+            (None, _) => {
+                self.tree = Some(Tree::with_capacity(value, self.capacity));
+                Some(0)
+            }
+            (Some(tree), Some(parent)) => Some(unsafe {tree.push_child(*parent, value)}.get()),
+            (Some(_), None) => {
+                self.fail(BuildErrorKind::MultipleRoots);
+                None
+            }
+        };
+        self.events += 1;
+        index
+    }
+
+    fn fail(&mut self, kind: BuildErrorKind) {
+        self.error = Some(BuildError{ event: self.events, kind });
+    }
+}
+
+impl<T: 'static> Default for TreeBuilder<T> {
+    fn default() -> Self {
+        TreeBuilder::new()
+    }
+}
@@ -1,10 +1,11 @@
 use crate::ref_mut::{RefMut, TreeRefMut};
 use crate::iter::ChildIter;
-use crate::reference::{TreeRef, Ref};
+use crate::reference::{TreeRef, Ref, DebugNode};
 use crate::tree::{Tree, Element};
 use crate::children_unique::ChildrenUnique;
 use std::ops::{DerefMut, Deref, Receiver};
 use crate::children_mut::ChildrenMut;
+use std::fmt::{Debug, Formatter};
 
 pub struct ChildUniq<'a, T>{
     inner: RefMut<'a, T>
@@ -34,14 +35,7 @@ impl<'a, T: 'static> ChildUniq<'a, T> {
     }
     pub fn clear_children(&mut self) {
         unsafe{
-            let buffer = self.buffer();
-
-            for child_index in self.raw_mut().childs.drain(..) {
-
-                ChildUniq::create(buffer, child_index.get()).clear_children();
-
-                (&mut*buffer).free(child_index);
-            }
+            (*self.buffer()).free_descendants(self.index());
         }
     }
     pub fn add_child(&mut self, value: T) -> ChildUniq<T> {
@@ -53,6 +47,21 @@ impl<'a, T: 'static> ChildUniq<'a, T> {
         }
     }
 
+    /// appends all values as children, the space for the children and their Elements is reserved
+    /// once for the lower bound of the size of values
+    pub fn add_children(&mut self, values: impl IntoIterator<Item = T>) {
+        let values = values.into_iter();
+        let additional = values.size_hint().0;
+        unsafe {
+            (*self.buffer()).reserve(additional);
+            self.raw_mut().children_mut().reserve(additional);
+            for value in values {
+                let index = (*self.buffer()).alloc_for(value, self.index());
+                self.raw_mut().children_mut().push(index);
+            }
+        }
+    }
+
     pub fn remove_child(&mut self, index: u32) -> T {
         unsafe {
             let buffer = self.buffer();
@@ -61,9 +70,9 @@ impl<'a, T: 'static> ChildUniq<'a, T> {
 
                 let id = childs.remove(index as usize);
 
-                ChildUniq::create(buffer, id.get()).clear_children();
+                (*buffer).free_descendants(id.get());
 
-                (&mut*self.buffer()).free(id)
+                (&mut*buffer).free(id)
             } else {
                 panic!("Index out of Bounds!")
             }
@@ -155,4 +164,10 @@ impl<'a, T: 'static> TreeRef for ChildUniq<'a, T> {
     fn get_ref<'b>(&'b self) -> Ref<'b, Self::Type> {
         self.inner.get_ref()
     }
-}
\ No newline at end of file
+}
+
+impl<'a, T: Debug + 'static> Debug for ChildUniq<'a, T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        DebugNode(self.get_ref()).fmt(f)
+    }
+}
@@ -21,6 +21,9 @@ impl<'a, T: 'static> ChildrenMut<'a, T> {
             ChildrenMut::create(self.buffer, self.child_indices)
         }
     }
+    pub(crate) fn buffer(&self) -> *mut Tree<T> {
+        self.buffer
+    }
     pub fn get_child_mut(&mut self, index: u32) -> RefMut<'a, T> {
         let child_index = self.child_indices.get(index as usize).expect("Index out of bounds!");
         unsafe {RefMut::create(child_index.get(), self.buffer)}
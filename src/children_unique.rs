@@ -34,7 +34,17 @@ impl<'a, T: 'static> ChildrenUnique<'a, T> {
     pub fn add_child(&mut self, value: T) -> ChildUniq<T> {
         self.inner.add_child(value)
     }
+    /// appends all values as children, see [`ChildUniq::add_children`]
+    pub fn add_children(&mut self, values: impl IntoIterator<Item = T>) {
+        self.inner.add_children(values)
+    }
     pub fn count(&self) -> u32 {
         self.inner.children_count()
     }
-}
\ No newline at end of file
+}
+
+impl<'a, T: 'static> Extend<T> for ChildrenUnique<'a, T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, values: I) {
+        self.add_children(values)
+    }
+}
@@ -0,0 +1,146 @@
+use std::fmt::Display;
+use std::io;
+use std::io::Write;
+use crate::tree::Tree;
+use crate::reference::{Ref, TreeRef};
+
+type NodeFn<'a, T> = Box<dyn Fn(Ref<T>) -> String + 'a>;
+type EdgeFn<'a, T> = Box<dyn Fn(Ref<T>, Ref<T>) -> String + 'a>;
+
+/// The options of [`Tree::write_dot`], they contain the closures which produce the labels and
+/// attributes of the nodes and edges.
+pub struct DotOptions<'a, T> {
+    node_label: NodeFn<'a, T>,
+    node_attributes: Option<NodeFn<'a, T>>,
+    edge_attributes: Option<EdgeFn<'a, T>>,
+    show_indices: bool,
+    show_free_list: bool,
+}
+
+impl<'a, T: 'static> DotOptions<'a, T> {
+    /// creates new options, which label the nodes with the given closure. The label is escaped.
+    pub fn new(node_label: impl Fn(Ref<T>) -> String + 'a) -> Self {
+        DotOptions {
+            node_label: Box::new(node_label),
+            node_attributes: None,
+            edge_attributes: None,
+            show_indices: false,
+            show_free_list: false,
+        }
+    }
+
+    /// sets a closure returning additional attributes for a node, like `color=red, shape=box`
+    pub fn node_attributes(mut self, attributes: impl Fn(Ref<T>) -> String + 'a) -> Self {
+        self.node_attributes = Some(Box::new(attributes));
+        self
+    }
+
+    /// sets a closure returning the attributes of the edge from parent to child
+    pub fn edge_attributes(mut self, attributes: impl Fn(Ref<T>, Ref<T>) -> String + 'a) -> Self {
+        self.edge_attributes = Some(Box::new(attributes));
+        self
+    }
+
+    /// prefixes every label with the index of the node in the pool
+    pub fn show_indices(mut self, show: bool) -> Self {
+        self.show_indices = show;
+        self
+    }
+
+    /// adds the unused Elements as dashed nodes, linked in the order of the free queue
+    pub fn show_free_list(mut self, show: bool) -> Self {
+        self.show_free_list = show;
+        self
+    }
+}
+
+impl<'a, T: Display + 'static> Default for DotOptions<'a, T> {
+    fn default() -> Self {
+        Self::new(|node|(*node).to_string())
+    }
+}
+
+impl<T: 'static> Tree<T> {
+    /// returns the Tree in the DOT language of Graphviz, see [`Tree::write_dot`]
+    pub fn to_dot_with(&self, options: &DotOptions<T>) -> String {
+        let mut out = Vec::new();
+        self.write_dot(&mut out, options).expect("writing to a Vec failed!");
+        String::from_utf8(out).expect("DOT output is valid UTF-8")
+    }
+
+    /// writes the Tree as a digraph in the DOT language of Graphviz. Nodes are named by their index
+    /// in the pool, the unused Elements are named free followed by their index.
+    pub fn write_dot(&self, out: &mut impl Write, options: &DotOptions<T>) -> io::Result<()> {
+        writeln!(out, "digraph tree {{")?;
+        for node in self.top().pre_order() {
+            let index = node.index();
+            let mut label = (options.node_label)(node.get_ref());
+            if options.show_indices {
+                label = format!("{}: {}", index, label);
+            }
+            write!(out, "    n{} [label=\"{}\"", index, escape(&label))?;
+            if let Some(attributes) = &options.node_attributes {
+                write_attributes(out, &attributes(node.get_ref()))?;
+            }
+            writeln!(out, "];")?;
+
+            if index != 0 {
+                let parent = unsafe {self.get_raw(index).parent()};
+                write!(out, "    n{} -> n{}", parent, index)?;
+                if let Some(attributes) = &options.edge_attributes {
+                    let attributes = attributes(unsafe {self.get_unchecked(parent)}, node.get_ref());
+                    if !attributes.is_empty() {
+                        write!(out, " [{}]", attributes)?;
+                    }
+                }
+                writeln!(out, ";")?;
+            }
+        }
+
+        if options.show_free_list && self.first_free().is_some() {
+            writeln!(out, "    free [shape=point];")?;
+            let mut previous = String::from("free");
+            let mut next = self.first_free();
+            while let Some(index) = next {
+                if options.show_indices {
+                    writeln!(out, "    free{} [label=\"{}: unused\", style=dashed];", index, index)?;
+                } else {
+                    writeln!(out, "    free{} [label=\"unused\", style=dashed];", index)?;
+                }
+                writeln!(out, "    {} -> free{} [style=dashed];", previous, index)?;
+                previous = format!("free{}", index);
+                next = unsafe {self.get_raw(index.get()).next_free()};
+            }
+        }
+        writeln!(out, "}}")
+    }
+}
+
+impl<T: Display + 'static> Tree<T> {
+    /// returns the Tree in the DOT language of Graphviz with the values as labels
+    pub fn to_dot(&self) -> String {
+        self.to_dot_with(&DotOptions::default())
+    }
+}
+
+fn write_attributes(out: &mut impl Write, attributes: &str) -> io::Result<()> {
+    if attributes.is_empty() {
+        Ok(())
+    } else {
+        write!(out, ", {}", attributes)
+    }
+}
+
+/// escapes a string for a quoted DOT identifier
+fn escape(label: &str) -> String {
+    let mut escaped = String::with_capacity(label.len());
+    for c in label.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
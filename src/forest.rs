@@ -0,0 +1,159 @@
+use std::num::NonZeroU32;
+use crate::tree::Tree;
+use crate::reference::Ref;
+use crate::ref_unique::RefUniq;
+use crate::ref_mut::RefMut;
+use crate::iter::ChildIter;
+use std::fmt::{Debug, Formatter};
+
+/// Forest is a possibly empty collection of trees, which share a single pool of Elements.
+/// The Element at index 0 is unused and holds the indices of the roots as its childs, therefore
+/// the roots are stored at nonzero indices like all other nodes and can be accessed through the
+/// same Ref and RefUniq types as the nodes of a Tree.
+///
+/// Indices named index refer to the position in the list of roots, like in RefUniq::remove_child.
+/// Indices of nodes inside the pool are named node.
+pub struct Forest<T> {
+    tree: Tree<T>,
+}
+
+impl<T: 'static> Forest<T> {
+    /// creates a new Forest without any roots
+    pub fn new() -> Self {
+        Forest {
+            tree: Tree::without_root(),
+        }
+    }
+
+    /// adds a new root with the given value and returns an unique reference to it
+    pub fn add_root(&mut self, value: T) -> RefUniq<T> {
+        unsafe {
+            let node = self.tree.push_child(0, value);
+            self.tree.get_unchecked_mut(node.get())
+        }
+    }
+
+    /// removes the root at index with its whole tree and returns the value of the root
+    ///
+    /// #Panics
+    /// Panics if index is out of bounds
+    pub fn remove_root(&mut self, index: u32) -> T {
+        let node = self.root_node(index);
+        unsafe {
+            self.tree.detach(node);
+            self.tree.free_descendants(node.get());
+            self.tree.free(node)
+        }
+    }
+
+    /// returns the number of roots
+    pub fn root_count(&self) -> u32 {
+        self.roots_indices().len() as u32
+    }
+
+    /// returns true if the Forest has no roots
+    pub fn is_empty(&self) -> bool {
+        self.roots_indices().is_empty()
+    }
+
+    /// returns a reference to the root at index
+    ///
+    /// #Panics
+    /// Panics if index is out of bounds
+    pub fn root(&self, index: u32) -> Ref<T> {
+        let node = self.root_node(index);
+        unsafe {self.tree.get_unchecked(node.get())}
+    }
+
+    /// returns an unique reference to the root at index
+    ///
+    /// #Panics
+    /// Panics if index is out of bounds
+    pub fn root_mut(&mut self, index: u32) -> RefUniq<T> {
+        let node = self.root_node(index);
+        unsafe {self.tree.get_unchecked_mut(node.get())}
+    }
+
+    /// iterates over all roots in the order they were added
+    pub fn roots(&self) -> ChildIter<T, Ref<T>> {
+        unsafe {
+            ChildIter::new(&self.tree, self.roots_indices())
+        }
+    }
+
+    /// iterates mutably over all roots in the order they were added
+    pub fn roots_mut(&mut self) -> ChildIter<T, RefMut<T>> {
+        unsafe {
+            let buffer = &mut self.tree as *mut Tree<T>;
+            ChildIter::new(buffer, (*buffer).get_raw(0).childs())
+        }
+    }
+
+    /// returns the node at the given pool index, if it is used
+    pub fn get_index(&self, node: u32) -> Option<Ref<T>> {
+        self.tree.get_index(node)
+    }
+
+    /// returns an unique reference to the node at the given pool index, if it is used
+    pub fn get_index_mut(&mut self, node: u32) -> Option<RefUniq<T>> {
+        self.tree.get_index_mut(node)
+    }
+
+    /// moves the node with its subtree to the end of the children of new_parent. Both are pool
+    /// indices and may belong to different roots.
+    ///
+    /// #Panics
+    /// Panics if one of the nodes is unused or if new_parent is part of the subtree of node
+    pub fn move_subtree(&mut self, node: u32, new_parent: u32) {
+        let node = self.used_node(node);
+        let new_parent = self.used_node(new_parent);
+        unsafe {self.tree.reparent(node, new_parent.get())}
+    }
+
+    /// detaches the node with its subtree from its parent and adds it as the last root. If node is
+    /// already a root, it is moved to the end of the roots.
+    ///
+    /// #Panics
+    /// Panics if node is unused
+    pub fn make_root(&mut self, node: u32) {
+        let node = self.used_node(node);
+        unsafe {self.tree.reparent(node, 0)}
+    }
+
+    /// removes the root at index from the Forest and returns its tree
+    ///
+    /// #Panics
+    /// Panics if index is out of bounds
+    pub fn take_root(&mut self, index: u32) -> Tree<T> {
+        let node = self.root_node(index);
+        unsafe {self.tree.take_subtree(node)}
+    }
+
+    fn roots_indices(&self) -> &[NonZeroU32] {
+        unsafe {self.tree.get_raw(0).childs()}
+    }
+
+    fn root_node(&self, index: u32) -> NonZeroU32 {
+        *self.roots_indices().get(index as usize).expect("Index out of Bounds!")
+    }
+
+    fn used_node(&self, node: u32) -> NonZeroU32 {
+        if self.tree.get_index(node).is_none() {
+            panic!("node {} is not part of the Forest!", node);
+        }
+        // get_index returns None for 0, since the Element at 0 is unused
+        unsafe {NonZeroU32::new_unchecked(node)}
+    }
+}
+
+impl<T: 'static> Default for Forest<T> {
+    fn default() -> Self {
+        Forest::new()
+    }
+}
+
+impl<T: Debug + 'static> Debug for Forest<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.roots()).finish()
+    }
+}
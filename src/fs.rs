@@ -0,0 +1,235 @@
+use std::fmt::{Display, Formatter};
+use std::error::Error;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use crate::tree::Tree;
+
+/// The kind of an entry of the file system
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum FsKind {
+    File,
+    Directory,
+    /// a symbolic link which was not followed
+    Symlink,
+    Other,
+}
+
+/// A node of a Tree built by [`Tree::from_dir`]
+#[derive(Clone, Debug, PartialEq)]
+pub struct FsEntry {
+    /// the file name, not valid UTF-8 is replaced lossy
+    pub name: String,
+    pub kind: FsKind,
+    /// the size in bytes as reported by the metadata
+    pub size: u64,
+    /// the time of the last modification, if the platform supports it
+    pub mtime: Option<SystemTime>,
+}
+
+impl Display for FsEntry {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.name)
+    }
+}
+
+/// The options of [`Tree::from_dir`]
+#[derive(Clone, Debug, Default)]
+pub struct DirOptions {
+    /// reads the targets of symbolic links, links to a directory containing the link are not
+    /// descended into and reported as error
+    pub follow_symlinks: bool,
+    /// the maximal depth of the nodes, the root has depth 0
+    pub max_depth: Option<usize>,
+    /// patterns of names which are skipped, `*` matches any sequence and `?` any single character
+    pub ignore: Vec<String>,
+    /// records errors and continues with the next entry instead of returning the first error
+    pub collect_errors: bool,
+}
+
+/// The error of a single path while walking a directory
+#[derive(Debug)]
+pub struct FsError {
+    pub path: PathBuf,
+    pub error: io::Error,
+}
+
+impl Display for FsError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.path.display(), self.error)
+    }
+}
+
+impl Error for FsError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+impl Tree<FsEntry> {
+    /// walks the directory at path and returns a Tree with one node per entry, the children of a
+    /// node are sorted by name. If errors are collected, they are returned next to the Tree,
+    /// otherwise the first error is returned. An error for path itself is always returned.
+    pub fn from_dir(path: impl AsRef<Path>, options: &DirOptions) -> Result<(Self, Vec<FsError>), FsError> {
+        let path = path.as_ref();
+        let metadata = read_metadata(path, options.follow_symlinks)?;
+        let name = path.file_name().unwrap_or(path.as_os_str()).to_string_lossy().into_owned();
+        let mut tree = Tree::new(entry(name, &metadata));
+        let mut errors = Vec::new();
+        // the canonical paths of the directories by their index, to detect loops of symbolic links
+        let mut canonical = Vec::new();
+
+        // the directories which still have to be read with their index and depth
+        let mut pending = Vec::new();
+        if tree.top().kind == FsKind::Directory {
+            pending.push((0, path.to_path_buf(), 0));
+        }
+        while let Some((index, path, depth)) = pending.pop() {
+            if options.max_depth.is_some_and(|max|depth >= max) {
+                continue;
+            }
+            if options.follow_symlinks {
+                match fs::canonicalize(&path) {
+                    Ok(real) => {
+                        if canonical.len() <= index as usize {
+                            canonical.resize(index as usize + 1, None);
+                        }
+                        if is_loop(&tree, &canonical, index, &real) {
+                            let error = io::Error::other("symbolic link to an ancestor directory");
+                            report(&mut errors, options, FsError{ path, error })?;
+                            continue;
+                        }
+                        canonical[index as usize] = Some(real);
+                    }
+                    Err(error) => {
+                        report(&mut errors, options, FsError{ path, error })?;
+                        continue;
+                    }
+                }
+            }
+
+            let mut children = match read_children(&path, options) {
+                Ok(children) => children,
+                Err(error) => {
+                    report(&mut errors, options, error)?;
+                    continue;
+                }
+            };
+            children.sort_by(|(a, _, _), (b, _, _)|a.name.cmp(&b.name));
+            let first = pending.len();
+            for (child, child_path, result) in children {
+                if let Some(error) = result {
+                    report(&mut errors, options, error)?;
+                    continue;
+                }
+                let directory = child.kind == FsKind::Directory;
+                let child = unsafe {tree.push_child(index, child)};
+                if directory {
+                    pending.push((child.get(), child_path, depth + 1));
+                }
+            }
+            // the first child is read first
+            pending[first..].reverse();
+        }
+        Ok((tree, errors))
+    }
+}
+
+fn entry(name: String, metadata: &fs::Metadata) -> FsEntry {
+    let kind = if metadata.file_type().is_symlink() {
+        FsKind::Symlink
+    } else if metadata.is_dir() {
+        FsKind::Directory
+    } else if metadata.is_file() {
+        FsKind::File
+    } else {
+        FsKind::Other
+    };
+    FsEntry {
+        name,
+        kind,
+        size: metadata.len(),
+        mtime: metadata.modified().ok(),
+    }
+}
+
+fn read_metadata(path: &Path, follow_symlinks: bool) -> Result<fs::Metadata, FsError> {
+    let metadata = if follow_symlinks {
+        fs::metadata(path)
+    } else {
+        fs::symlink_metadata(path)
+    };
+    metadata.map_err(|error|FsError{ path: path.to_path_buf(), error })
+}
+
+/// returns the entries of the directory, which are not ignored, with their paths. An entry whose
+/// metadata could not be read is returned with its error.
+fn read_children(path: &Path, options: &DirOptions) -> Result<Vec<(FsEntry, PathBuf, Option<FsError>)>, FsError> {
+    let error = |error|FsError{ path: path.to_path_buf(), error };
+    let mut children = Vec::new();
+    for child in fs::read_dir(path).map_err(error)? {
+        let child = child.map_err(error)?;
+        let name = child.file_name().to_string_lossy().into_owned();
+        if options.ignore.iter().any(|pattern|matches_glob(pattern, &name)) {
+            continue;
+        }
+        let path = child.path();
+        match read_metadata(&path, options.follow_symlinks) {
+            Ok(metadata) => children.push((entry(name, &metadata), path, None)),
+            Err(error) => children.push((FsEntry{ name, kind: FsKind::Other, size: 0, mtime: None }, path, Some(error))),
+        }
+    }
+    Ok(children)
+}
+
+/// returns the error if errors are not collected, otherwise it is pushed to errors
+fn report(errors: &mut Vec<FsError>, options: &DirOptions, error: FsError) -> Result<(), FsError> {
+    if options.collect_errors {
+        errors.push(error);
+        Ok(())
+    } else {
+        Err(error)
+    }
+}
+
+/// returns true if an ancestor of the node at index has the canonical path real
+fn is_loop(tree: &Tree<FsEntry>, canonical: &[Option<PathBuf>], mut index: u32, real: &Path) -> bool {
+    while index != 0 {
+        index = unsafe {tree.get_raw(index)}.parent();
+        if canonical.get(index as usize).is_some_and(|path|path.as_deref() == Some(real)) {
+            return true;
+        }
+    }
+    false
+}
+
+/// matches name against a pattern with the wildcards `*` and `?`
+fn matches_glob(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    let (mut p, mut n) = (0, 0);
+    // the position after the last `*` and the position in name it currently matches up to
+    let mut backtrack = None;
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                p += 1;
+                backtrack = Some((p, n));
+            }
+            Some(c) if *c == '?' || *c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match backtrack {
+                Some((star, matched)) => {
+                    p = star;
+                    n = matched + 1;
+                    backtrack = Some((star, matched + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|c|*c == '*')
+}
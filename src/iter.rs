@@ -1,8 +1,10 @@
 use smallvec::alloc::slice::Iter;
 use crate::tree::Tree;
 use std::marker::PhantomData;
-use crate::reference::TreeRef;
+use crate::reference::{TreeRef, Ref};
+use crate::ref_mut::RefMut;
 use std::num::NonZeroU32;
+use std::iter::FromIterator;
 
 pub struct ChildIter<'a, T, R: 'a + TreeRef<Type=T>> {
     buffer: *const Tree<T>,
@@ -29,4 +31,198 @@ impl<'a, T, R: 'a + TreeRef<Type=T>> Iterator for ChildIter<'a, T, R> {
             R::create(buffer, index.get())
         })
     }
-}
\ No newline at end of file
+}
+/// PreOrder iterates over a node and all of its descendants in pre-order
+pub struct PreOrder<'a, T> {
+    buffer: &'a Tree<T>,
+    stack: Vec<u32>,
+}
+
+impl<'a, T: 'static> PreOrder<'a, T> {
+    /// creates a PreOrder iterator starting at the node at index
+    ///
+    /// # Safety
+    /// index must be a used Element of buffer. The Tree is borrowed for 'a, so it can not change
+    /// while the iterator exists.
+    pub unsafe fn new(buffer: &'a Tree<T>, index: u32) -> Self {
+        PreOrder {
+            buffer,
+            stack: vec![index],
+        }
+    }
+}
+
+impl<'a, T: 'static> Iterator for PreOrder<'a, T> {
+    type Item = Ref<'a, T>;
+
+    fn next(&mut self) -> Option<Ref<'a, T>> {
+        let index = self.stack.pop()?;
+        unsafe {
+            let childs = self.buffer.get_raw(index).childs();
+            self.stack.extend(childs.iter().rev().map(|child|child.get()));
+            Some(Ref::create(index, self.buffer))
+        }
+    }
+}
+
+/// PreOrderMut visits a node and all of its descendants in pre-order with a RefMut, each exactly
+/// once. It is a cursor instead of an Iterator, since every RefMut borrows the cursor, so only one
+/// of them can exist at a time:
+///
+/// ```
+/// use pool_tree::tree;
+/// use pool_tree::ref_mut::TreeRefMut;
+///
+/// let mut tree = tree![1 => [2, 3]];
+/// let mut top = tree.mut_top();
+/// let mut nodes = top.pre_order_mut();
+/// while let Some(mut node) = nodes.next_mut() {
+///     *node += 1;
+/// }
+/// assert_eq!("2(3, 4)", tree.to_string());
+/// ```
+pub struct PreOrderMut<'a, T> {
+    buffer: *mut Tree<T>,
+    stack: Vec<u32>,
+    _p: PhantomData<&'a mut Tree<T>>,
+}
+
+impl<'a, T: 'static> PreOrderMut<'a, T> {
+    /// creates a PreOrderMut cursor starting at the node at index
+    ///
+    /// # Safety
+    /// buffer must point to a Tree which stays valid and is not moved during 'a, and index must be
+    /// a used Element of it. The cursor has exclusive access to the subtree of index during 'a, so
+    /// no other reference to a node of this subtree may be used while it exists.
+    pub unsafe fn new(buffer: *mut Tree<T>, index: u32) -> Self {
+        PreOrderMut {
+            buffer,
+            stack: vec![index],
+            _p: PhantomData,
+        }
+    }
+
+    /// returns the next node, the RefMut can not change the structure of the Tree
+    pub fn next_mut(&mut self) -> Option<RefMut<T>> {
+        let index = self.stack.pop()?;
+        unsafe {
+            let childs = (*self.buffer).get_raw(index).childs();
+            self.stack.extend(childs.iter().rev().map(|child|child.get()));
+            Some(RefMut::create(index, self.buffer))
+        }
+    }
+}
+
+/// An event of a depth-first traversal, see [`Edges`]
+pub enum Edge<'a, T> {
+    /// the traversal reaches the node, before its children
+    Open(Ref<'a, T>),
+    /// the traversal leaves the node, after its children
+    Close(Ref<'a, T>),
+}
+
+/// Edges iterates over the subtree of a node depth-first and yields an Open event when a node is
+/// reached and a Close event when it is left, so the events are balanced like parentheses
+pub struct Edges<'a, T> {
+    buffer: &'a Tree<T>,
+    /// the opened nodes and the position of their next child
+    stack: Vec<(u32, usize)>,
+    next: Option<u32>,
+}
+
+impl<'a, T: 'static> Edges<'a, T> {
+    /// creates an Edges iterator starting at the node at index
+    ///
+    /// # Safety
+    /// index must be a used Element of buffer. The Tree is borrowed for 'a, so it can not change
+    /// while the iterator exists.
+    pub unsafe fn new(buffer: &'a Tree<T>, index: u32) -> Self {
+        Edges {
+            buffer,
+            stack: Vec::new(),
+            next: Some(index),
+        }
+    }
+}
+
+impl<'a, T: 'static> Iterator for Edges<'a, T> {
+    type Item = Edge<'a, T>;
+
+    fn next(&mut self) -> Option<Edge<'a, T>> {
+        if let Some(index) = self.next.take() {
+            self.stack.push((index, 0));
+            return Some(Edge::Open(unsafe {Ref::create(index, self.buffer)}));
+        }
+        let (index, position) = self.stack.last_mut()?;
+        let index = *index;
+        match unsafe {self.buffer.get_raw(index)}.childs().get(*position) {
+            Some(child) => {
+                *position += 1;
+                self.stack.push((child.get(), 0));
+                Some(Edge::Open(unsafe {Ref::create(child.get(), self.buffer)}))
+            }
+            None => {
+                self.stack.pop();
+                Some(Edge::Close(unsafe {Ref::create(index, self.buffer)}))
+            }
+        }
+    }
+}
+
+/// IntoIter consumes a Tree and yields the depth and the value of every node in pre-order, the
+/// root has depth 0. The values which were not yielded are dropped with the iterator.
+pub struct IntoIter<T> {
+    tree: Tree<T>,
+    stack: Vec<(u32, usize)>,
+}
+
+impl<T: 'static> IntoIterator for Tree<T> {
+    type Item = (usize, T);
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> IntoIter<T> {
+        IntoIter {
+            tree: self,
+            stack: vec![(0, 0)],
+        }
+    }
+}
+
+impl<T: 'static> Iterator for IntoIter<T> {
+    type Item = (usize, T);
+
+    fn next(&mut self) -> Option<(usize, T)> {
+        let (index, depth) = self.stack.pop()?;
+        let element = unsafe {self.tree.get_raw_mut(index)};
+        let childs = std::mem::take(&mut element.childs);
+        self.stack.extend(childs.iter().rev().map(|child|(child.get(), depth + 1)));
+        Some((depth, element.set_unused(None)))
+    }
+}
+
+/// collects a Tree from the depths and values of its nodes in pre-order, like they are yielded by
+/// [`IntoIter`]
+///
+/// #Panics
+/// Panics if the iterator is empty, the first depth is not 0 or a depth is more than one greater
+/// than the previous one
+impl<T: 'static> FromIterator<(usize, T)> for Tree<T> {
+    fn from_iter<I: IntoIterator<Item = (usize, T)>>(iter: I) -> Self {
+        let mut iter = iter.into_iter();
+        let mut tree = match iter.next() {
+            Some((0, root)) => Tree::new(root),
+            Some(_) => panic!("the first node must have depth 0!"),
+            None => panic!("collected a Tree from an empty iterator!"),
+        };
+        tree.reserve(iter.size_hint().0);
+        // the indices of the last node at every depth
+        let mut path = vec![0];
+        for (depth, value) in iter {
+            assert!(depth > 0 && depth <= path.len(), "depth {} follows depth {}!", depth, path.len() - 1);
+            path.truncate(depth);
+            let index = unsafe {tree.push_child(path[depth - 1], value)};
+            path.push(index.get());
+        }
+        tree
+    }
+}
@@ -0,0 +1,381 @@
+use std::fmt::{Display, Formatter, Write};
+use std::error::Error;
+use crate::tree::Tree;
+use crate::reference::TreeRef;
+
+/// The value of a JSON node. Arrays and objects have no payload, their elements and members are
+/// the children of the node.
+#[derive(Clone, Debug, PartialEq)]
+pub enum JsonValue {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array,
+    Object,
+}
+
+/// A node of a JSON document. Members of an object have a key, all other nodes have none.
+#[derive(Clone, Debug, PartialEq)]
+pub struct JsonNode {
+    pub key: Option<String>,
+    pub value: JsonValue,
+}
+
+/// The error returned by [`Tree::from_json`]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct JsonError {
+    /// the byte offset in the input at which the error occurred
+    pub position: usize,
+    pub kind: JsonErrorKind,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum JsonErrorKind {
+    UnexpectedEnd,
+    UnexpectedChar(char),
+    InvalidNumber,
+    InvalidEscape,
+    /// a control character inside of a string
+    InvalidChar,
+}
+
+impl Display for JsonError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match &self.kind {
+            JsonErrorKind::UnexpectedEnd => write!(f, "unexpected end of input at {}", self.position),
+            JsonErrorKind::UnexpectedChar(c) => write!(f, "unexpected character {:?} at {}", c, self.position),
+            JsonErrorKind::InvalidNumber => write!(f, "invalid number at {}", self.position),
+            JsonErrorKind::InvalidEscape => write!(f, "invalid escape sequence at {}", self.position),
+            JsonErrorKind::InvalidChar => write!(f, "unescaped control character at {}", self.position),
+        }
+    }
+}
+
+impl Error for JsonError {}
+
+impl Tree<JsonNode> {
+    /// parses a JSON document. The nodes are allocated in document order, so the pool index of a
+    /// node is its position in a pre-order traversal.
+    pub fn from_json(text: &str) -> Result<Self, JsonError> {
+        let mut parser = Parser{ text, position: 0 };
+        parser.whitespace();
+        let root = parser.value()?;
+        // the open containers, whether they are an object and whether they have no member yet
+        let mut stack = Vec::new();
+        if root == JsonValue::Array || root == JsonValue::Object {
+            stack.push((0, root == JsonValue::Object, true));
+        }
+        let mut tree = Tree::new(JsonNode{ key: None, value: root });
+
+        while let Some(&(index, object, first)) = stack.last() {
+            parser.whitespace();
+            let close = if object { '}' } else { ']' };
+            match parser.peek() {
+                Some(c) if c == close => {
+                    parser.position += 1;
+                    stack.pop();
+                    continue;
+                }
+                Some(',') if !first => {
+                    parser.position += 1;
+                    parser.whitespace();
+                }
+                _ if first => {}
+                Some(c) => return Err(parser.error(JsonErrorKind::UnexpectedChar(c))),
+                None => return Err(parser.error(JsonErrorKind::UnexpectedEnd)),
+            }
+            stack.last_mut().unwrap().2 = false;
+
+            let key = if object {
+                parser.expect('"')?;
+                let key = parser.string()?;
+                parser.whitespace();
+                parser.expect(':')?;
+                parser.whitespace();
+                Some(key)
+            } else {
+                None
+            };
+            let value = parser.value()?;
+            let container = value == JsonValue::Array || value == JsonValue::Object;
+            let object = value == JsonValue::Object;
+            let child = unsafe {tree.push_child(index, JsonNode{ key, value })};
+            if container {
+                stack.push((child.get(), object, true));
+            }
+        }
+
+        parser.whitespace();
+        match parser.peek() {
+            None => Ok(tree),
+            Some(c) => Err(parser.error(JsonErrorKind::UnexpectedChar(c))),
+        }
+    }
+
+    /// writes the document without any whitespace
+    pub fn to_json(&self) -> String {
+        let mut out = String::new();
+        self.write_json(&mut out, false).unwrap();
+        out
+    }
+
+    /// writes the document with every element and member on its own line, indented by two spaces
+    pub fn to_json_pretty(&self) -> String {
+        let mut out = String::new();
+        self.write_json(&mut out, true).unwrap();
+        out
+    }
+
+    /// writes the document, the key of the root is ignored. Numbers which are not finite are
+    /// written as null.
+    pub fn write_json(&self, out: &mut impl Write, pretty: bool) -> std::fmt::Result {
+        let newline = |out: &mut dyn Write, depth: usize| -> std::fmt::Result {
+            if pretty {
+                out.write_char('\n')?;
+                for _ in 0..depth {
+                    out.write_str("  ")?;
+                }
+            }
+            Ok(())
+        };
+
+        // the open containers and the position of their next child
+        let mut stack: Vec<(u32, usize)> = Vec::new();
+        if write_value(out, unsafe {self.get_raw(0).get_value()}, self.top().children_count() == 0)? {
+            stack.push((0, 0));
+        }
+        while let Some((index, position)) = stack.last_mut() {
+            let element = unsafe {self.get_raw(*index)};
+            let object = unsafe {element.get_value()}.value == JsonValue::Object;
+            if let Some(child) = element.childs().get(*position) {
+                if *position > 0 {
+                    out.write_char(',')?;
+                }
+                *position += 1;
+                newline(out, stack.len())?;
+
+                let child_element = unsafe {self.get_raw(child.get())};
+                let node = unsafe {child_element.get_value()};
+                if object {
+                    write_string(out, node.key.as_deref().unwrap_or(""))?;
+                    out.write_str(if pretty { ": " } else { ":" })?;
+                }
+                if write_value(out, node, child_element.childs().is_empty())? {
+                    stack.push((child.get(), 0));
+                }
+            } else {
+                stack.pop();
+                newline(out, stack.len())?;
+                out.write_char(if object { '}' } else { ']' })?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// writes a scalar or the opening bracket of a container and returns true if the children of the
+/// container have to be written. Empty containers are closed immediately.
+fn write_value(out: &mut impl Write, node: &JsonNode, empty: bool) -> Result<bool, std::fmt::Error> {
+    match &node.value {
+        JsonValue::Null => out.write_str("null")?,
+        JsonValue::Bool(value) => write!(out, "{}", value)?,
+        JsonValue::Number(value) if value.is_finite() => write!(out, "{}", value)?,
+        JsonValue::Number(_) => out.write_str("null")?,
+        JsonValue::String(value) => write_string(out, value)?,
+        JsonValue::Array if empty => out.write_str("[]")?,
+        JsonValue::Object if empty => out.write_str("{}")?,
+        JsonValue::Array => {
+            out.write_char('[')?;
+            return Ok(true);
+        }
+        JsonValue::Object => {
+            out.write_char('{')?;
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+fn write_string(out: &mut impl Write, value: &str) -> std::fmt::Result {
+    out.write_char('"')?;
+    for c in value.chars() {
+        match c {
+            '"' => out.write_str("\\\"")?,
+            '\\' => out.write_str("\\\\")?,
+            '\n' => out.write_str("\\n")?,
+            '\r' => out.write_str("\\r")?,
+            '\t' => out.write_str("\\t")?,
+            c if (c as u32) < 0x20 => write!(out, "\\u{:04x}", c as u32)?,
+            c => out.write_char(c)?,
+        }
+    }
+    out.write_char('"')
+}
+
+struct Parser<'a> {
+    text: &'a str,
+    position: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<char> {
+        self.text[self.position..].chars().next()
+    }
+
+    fn error(&self, kind: JsonErrorKind) -> JsonError {
+        JsonError{ position: self.position, kind }
+    }
+
+    fn whitespace(&mut self) {
+        let rest = &self.text[self.position..];
+        self.position += rest.len() - rest.trim_start_matches(&[' ', '\t', '\n', '\r'][..]).len();
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), JsonError> {
+        match self.peek() {
+            Some(c) if c == expected => {
+                self.position += 1;
+                Ok(())
+            }
+            Some(c) => Err(self.error(JsonErrorKind::UnexpectedChar(c))),
+            None => Err(self.error(JsonErrorKind::UnexpectedEnd)),
+        }
+    }
+
+    /// parses a scalar or the opening bracket of a container
+    fn value(&mut self) -> Result<JsonValue, JsonError> {
+        let rest = &self.text[self.position..];
+        for (literal, value) in &[("null", JsonValue::Null), ("true", JsonValue::Bool(true)), ("false", JsonValue::Bool(false))] {
+            if rest.starts_with(literal) {
+                self.position += literal.len();
+                return Ok(value.clone());
+            }
+        }
+        match self.peek() {
+            Some('[') => {
+                self.position += 1;
+                Ok(JsonValue::Array)
+            }
+            Some('{') => {
+                self.position += 1;
+                Ok(JsonValue::Object)
+            }
+            Some('"') => {
+                self.position += 1;
+                Ok(JsonValue::String(self.string()?))
+            }
+            Some(c) if c == '-' || c.is_ascii_digit() => self.number(),
+            Some(c) => Err(self.error(JsonErrorKind::UnexpectedChar(c))),
+            None => Err(self.error(JsonErrorKind::UnexpectedEnd)),
+        }
+    }
+
+    fn number(&mut self) -> Result<JsonValue, JsonError> {
+        let bytes = self.text.as_bytes();
+        let start = self.position;
+        let mut end = start;
+        let digits = |end: &mut usize| {
+            let from = *end;
+            while bytes.get(*end).is_some_and(u8::is_ascii_digit) {
+                *end += 1;
+            }
+            *end > from
+        };
+
+        if bytes.get(end) == Some(&b'-') {
+            end += 1;
+        }
+        let leading_zero = bytes.get(end) == Some(&b'0');
+        let mut valid = digits(&mut end) && !(leading_zero && end - start > 1 + (bytes[start] == b'-') as usize);
+        if valid && bytes.get(end) == Some(&b'.') {
+            end += 1;
+            valid = digits(&mut end);
+        }
+        if valid && matches!(bytes.get(end), Some(b'e') | Some(b'E')) {
+            end += 1;
+            if matches!(bytes.get(end), Some(b'+') | Some(b'-')) {
+                end += 1;
+            }
+            valid = digits(&mut end);
+        }
+        if !valid {
+            return Err(self.error(JsonErrorKind::InvalidNumber));
+        }
+        self.position = end;
+        self.text[start..end].parse().map(JsonValue::Number).map_err(|_|JsonError{ position: start, kind: JsonErrorKind::InvalidNumber })
+    }
+
+    /// parses the rest of a string after the opening quote
+    fn string(&mut self) -> Result<String, JsonError> {
+        let mut value = String::new();
+        loop {
+            let c = self.peek().ok_or_else(||self.error(JsonErrorKind::UnexpectedEnd))?;
+            match c {
+                '"' => {
+                    self.position += 1;
+                    return Ok(value);
+                }
+                '\\' => {
+                    self.position += 1;
+                    let simple = match self.peek() {
+                        Some('"') => '"',
+                        Some('\\') => '\\',
+                        Some('/') => '/',
+                        Some('b') => '\u{8}',
+                        Some('f') => '\u{c}',
+                        Some('n') => '\n',
+                        Some('r') => '\r',
+                        Some('t') => '\t',
+                        Some('u') => {
+                            self.position += 1;
+                            value.push(self.unicode_escape()?);
+                            continue;
+                        }
+                        Some(_) => return Err(self.error(JsonErrorKind::InvalidEscape)),
+                        None => return Err(self.error(JsonErrorKind::UnexpectedEnd)),
+                    };
+                    value.push(simple);
+                    self.position += 1;
+                }
+                c if (c as u32) < 0x20 => return Err(self.error(JsonErrorKind::InvalidChar)),
+                c => {
+                    value.push(c);
+                    self.position += c.len_utf8();
+                }
+            }
+        }
+    }
+
+    /// parses the four hex digits after `\u` and a following low surrogate escape
+    fn unicode_escape(&mut self) -> Result<char, JsonError> {
+        let high = self.hex()?;
+        let code = if (0xD800..0xDC00).contains(&high) {
+            if !self.text[self.position..].starts_with("\\u") {
+                return Err(self.error(JsonErrorKind::InvalidEscape));
+            }
+            self.position += 2;
+            let low = self.hex()?;
+            if !(0xDC00..0xE000).contains(&low) {
+                return Err(self.error(JsonErrorKind::InvalidEscape));
+            }
+            0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00)
+        } else {
+            high
+        };
+        std::char::from_u32(code).ok_or_else(||self.error(JsonErrorKind::InvalidEscape))
+    }
+
+    fn hex(&mut self) -> Result<u32, JsonError> {
+        if self.text.len() - self.position < 4 {
+            return Err(self.error(JsonErrorKind::UnexpectedEnd));
+        }
+        // the slice is None if it ends inside of a multi-byte character
+        let digits = match self.text.get(self.position..self.position + 4) {
+            Some(digits) if digits.chars().all(|c|c.is_ascii_hexdigit()) => digits,
+            _ => return Err(self.error(JsonErrorKind::InvalidEscape)),
+        };
+        self.position += 4;
+        Ok(u32::from_str_radix(digits, 16).unwrap())
+    }
+}
@@ -3,6 +3,9 @@
 #![allow(dead_code)]
 #![allow(incomplete_features)]
 
+#[macro_use]
+mod macros;
+
 pub mod node;
 pub mod tree;
 pub mod ref_unique;
@@ -13,6 +16,23 @@ pub mod iter;
 pub mod child_unique;
 pub mod children_mut;
 pub mod children_unique;
+pub mod forest;
+pub mod option_tree;
+pub mod parse;
+pub mod outline;
+pub mod dot;
+pub mod newick;
+pub mod json;
+pub mod sexpr;
+pub mod binary;
+pub mod structure;
+pub mod fs;
+pub mod builder;
+pub mod unfold;
+pub mod map;
+pub mod visitor;
+#[cfg(feature = "serde")]
+pub mod serialize;
 
 #[cfg(test)]
 mod tests {
@@ -40,4 +60,717 @@ mod tests {
         assert_eq!(5, *tree.top());
     }
 
+    #[test]
+    fn test_drop_values() {
+        use std::rc::Rc;
+
+        let value = Rc::new(());
+        let mut tree = Tree::new(value.clone());
+        let mut top = tree.mut_top();
+        top.add_child(value.clone()).add_child(value.clone());
+        top.add_child(value.clone());
+
+        drop(top.remove_child(0));
+        assert_eq!(3, Rc::strong_count(&value));
+
+        top.add_child(value.clone());
+        drop(tree);
+        assert_eq!(1, Rc::strong_count(&value));
+    }
+
+    #[test]
+    fn test_size() {
+        use std::mem::size_of;
+        use std::num::NonZeroU32;
+        use smallvec::SmallVec;
+        use crate::tree::Element;
+
+        // the former layout, which needs space for the discriminant of value
+        #[allow(dead_code)]
+        struct OptionElement<T> {
+            value: Option<T>,
+            parent_next_free: Option<NonZeroU32>,
+            childs: SmallVec<[NonZeroU32; 5]>,
+        }
+
+        // the used flag is stored in parent_next_free, so it takes no extra space
+        assert_eq!(size_of::<(u64, u32, SmallVec<[NonZeroU32; 5]>)>(), size_of::<Element<u64>>());
+        assert!(size_of::<Element<u64>>() < size_of::<OptionElement<u64>>());
+        assert!(size_of::<Element<u8>>() <= size_of::<OptionElement<u8>>());
+    }
+
+    #[test]
+    fn test_forest() {
+        use crate::forest::Forest;
+        use crate::reference::TreeRef;
+
+        let mut forest = Forest::new();
+        let a = forest.add_root(1).add_child(2).index();
+        forest.add_root(3);
+        forest.add_root(4).add_child(5);
+
+        forest.move_subtree(a, forest.root(1).index());
+        assert_eq!(vec![1, 3, 4], forest.roots().map(|root|*root).collect::<Vec<_>>());
+        assert_eq!(0, forest.root(0).children_count());
+        assert_eq!(2, *forest.root(1).get_child(0));
+
+        assert_eq!(1, forest.remove_root(0));
+        let tree = forest.take_root(0);
+        assert_eq!("3(2)", tree.to_string());
+        assert_eq!(1, forest.root_count());
+        assert!(forest.get_index_mut(0).is_none());
+        assert!(forest.root_mut(0).into_parent().is_err());
+
+        // removing deep nodes does not recurse
+        let mut forest = Forest::new();
+        let mut node = forest.add_root(0).index();
+        for depth in 1..100_000 {
+            node = forest.get_index_mut(node).unwrap().add_child(depth).index();
+        }
+        assert_eq!(0, forest.remove_root(0));
+        assert!(forest.is_empty());
+        let mut tree: Tree<usize> = (0..100_000).map(|depth|(depth, depth)).collect();
+        assert_eq!(1, tree.mut_top().remove_child(0));
+        assert_eq!("0", tree.to_string());
+    }
+
+    #[test]
+    fn test_option_tree() {
+        use crate::option_tree::OptionTree;
+
+        let mut tree = OptionTree::new();
+        assert!(tree.root().is_none());
+        assert_eq!(None, tree.replace_root(1));
+
+        let mut root = tree.root_mut().unwrap();
+        root.add_child(2);
+        root.add_child(3);
+        let capacity = tree.capacity();
+
+        assert_eq!(Some(1), tree.replace_root(4));
+        assert_eq!("4(2, 3)", tree.root().unwrap().to_string());
+        assert_eq!(Some(4), tree.take_root());
+        assert!(tree.is_empty());
+        assert_eq!(capacity, tree.capacity());
+
+        tree.set_root(5).add_child(6);
+        assert_eq!("5(6)", tree.into_tree().unwrap().to_string());
+    }
+
+    #[test]
+    fn test_reroot() {
+        use crate::tree::RerootMode;
+        use crate::reference::TreeRef;
+
+        let mut tree = Tree::new(0);
+        let mut top = tree.mut_top();
+        let mut one = top.add_child(1);
+        let one_index = one.index();
+        one.add_child(3);
+        let four_index = one.add_child(4).index();
+        top.add_child(2);
+        let mut four = tree.get_index_mut(four_index).unwrap();
+        let mut five = four.add_child(5);
+        let five_index = five.index();
+        five.add_child(6);
+
+        tree.reroot(five_index, RerootMode::Undirected);
+        assert_eq!("5(6, 4(1(3, 0(2))))", tree.to_string());
+        let old_root = tree.get_index_mut(five_index).unwrap();
+        assert_eq!(0, *old_root);
+        assert_eq!(1, *old_root.into_parent().ok().unwrap());
+
+        tree.reroot(one_index, RerootMode::Undirected);
+        assert_eq!("1(3, 0(2), 4(5(6)))", tree.to_string());
+
+        tree.reroot(four_index, RerootMode::Subtree);
+        assert_eq!("4(5(6))", tree.to_string());
+        tree.mut_top().add_child(7);
+        assert_eq!("4(5(6), 7)", tree.to_string());
+
+        // the dropped nodes are freed without recursion
+        let mut tree: Tree<usize> = (0..100_000).map(|depth|(depth, depth)).collect();
+        tree.reroot(99_999, RerootMode::Subtree);
+        assert_eq!("99999", tree.to_string());
+    }
+
+    #[test]
+    fn test_split_off() {
+        let mut tree = Tree::new(0);
+        let mut top = tree.mut_top();
+        let mut one = top.add_child(10);
+        one.add_child(1);
+        one.add_child(11).add_child(12);
+        top.add_child(2);
+        top.add_child(13);
+
+        let trees = tree.split_off(|node|*node >= 10);
+        assert_eq!("0(2)", tree.to_string());
+        let trees: Vec<String> = trees.iter().map(|tree|tree.to_string()).collect();
+        assert_eq!(vec!["10(1)", "11", "12", "13"], trees);
+    }
+
+    #[test]
+    fn test_parse() {
+        use crate::parse::{ParseTreeError, ParseTreeErrorKind};
+
+        let tree: Tree<u32> = " 1 ( 2, 3(4,5),6 ) ".parse().unwrap();
+        assert_eq!("1(2, 3(4, 5), 6)", tree.to_string());
+
+        let mut tree = Tree::new(String::from(" a(b), c\\"));
+        tree.mut_top().add_child(String::new());
+        tree.mut_top().add_child(String::from("x y "));
+        let text = tree.to_string();
+        assert_eq!("\\ a\\(b\\)\\, c\\\\(, x y\\ )", text);
+        assert_eq!(text, text.parse::<Tree<String>>().unwrap().to_string());
+
+        let mut tree = Tree::new(String::from(" "));
+        tree.mut_top().add_child(String::from("  "));
+        tree.mut_top().add_child(String::from("a  "));
+        assert_eq!("\\ (\\ \\ , a \\ )", tree.to_string());
+        assert_eq!(tree.to_string(), tree.to_string().parse::<Tree<String>>().unwrap().to_string());
+        let tree: Tree<f64> = "1(2.5)".parse().unwrap();
+        assert_eq!("1.00(2.50)", format!("{:.2}", tree));
+
+        assert_eq!(Err(ParseTreeError{ position: 4, kind: ParseTreeErrorKind::UnexpectedEnd }), "1(2(".parse::<Tree<String>>().map(|_|()));
+        assert_eq!(Err(ParseTreeError{ position: 4, kind: ParseTreeErrorKind::UnexpectedChar('(') }), "1(2)(3)".parse::<Tree<String>>().map(|_|()));
+        assert_eq!(Some(2), "1(x)".parse::<Tree<u32>>().err().map(|error|error.position));
+    }
+
+    #[test]
+    fn test_outline() {
+        use crate::outline::{OutlineOptions, OutlineErrorKind};
+
+        let tree: Tree<u32> = "1(2(3, 4), 5)".parse().unwrap();
+        let boxed = tree.to_outline_with(&OutlineOptions{ box_drawing: true, ..Default::default() });
+        assert_eq!("1\n├── 2\n│   ├── 3\n│   └── 4\n└── 5\n", boxed);
+
+        let tabs = tree.to_outline_with(&OutlineOptions{ indent: String::from("\t"), box_drawing: false });
+        assert_eq!("1\n\t2\n\t\t3\n\t\t4\n\t5\n", tabs);
+        for text in &[boxed, tabs, tree.to_outline(), String::from("1\n  2\n\n    3\n    4\n  5")] {
+            assert_eq!("1(2(3, 4), 5)", Tree::<u32>::from_outline(text).unwrap().to_string());
+        }
+
+        let error = Tree::<u32>::from_outline("1\n  2\n\t3").unwrap_err();
+        assert_eq!((3, OutlineErrorKind::InconsistentIndentation), (error.line, error.kind));
+        let error = Tree::<u32>::from_outline("1\n  2\n      3").unwrap_err();
+        assert_eq!((3, OutlineErrorKind::UnexpectedIndentation), (error.line, error.kind));
+        let error = Tree::<u32>::from_outline("1\n2").unwrap_err();
+        assert_eq!((2, OutlineErrorKind::MultipleRoots), (error.line, error.kind));
+    }
+
+    #[test]
+    fn test_debug() {
+        use crate::reference::TreeRef;
+
+        let mut tree: Tree<u32> = "1(2(3, 4), 5)".parse().unwrap();
+        assert_eq!("1 [2 [3, 4], 5]", format!("{:?}", tree));
+        assert_eq!("1 [\n    2 [\n        3,\n        4,\n    ],\n    5,\n]", format!("{:#?}", tree));
+
+        let mut top = tree.mut_top();
+        assert_eq!("2 [3, 4]", format!("{:?}", top.get_child_unique(0)));
+        top.remove_child(1);
+        assert!(format!("{:?}", tree.debug_pool()).contains("next_free: Some(4)"));
+
+        let mut tree = Tree::new(None);
+        tree.mut_top().add_child(Some(2));
+        assert_eq!("None [\n    Some(\n        2,\n    ),\n]", format!("{:#?}", tree));
+
+        // Debug does not recurse, so deep trees can be formatted
+        let mut tree = Tree::new(0);
+        let mut index = 0;
+        for depth in 1..100_000 {
+            index = tree.get_index_mut(index).unwrap().add_child(depth).index();
+        }
+        assert!(format!("{:?}", tree).ends_with(&("99998 [99999".to_string() + &"]".repeat(99_999))));
+    }
+
+    #[test]
+    fn test_dot() {
+        use crate::dot::DotOptions;
+        use crate::reference::Ref;
+
+        let mut tree: Tree<String> = "a(b, c\\\"d)".parse().unwrap();
+        assert_eq!("digraph tree {\n    n0 [label=\"a\"];\n    n1 [label=\"b\"];\n    n0 -> n1;\n    n2 [label=\"c\\\"d\"];\n    n0 -> n2;\n}\n", tree.to_dot());
+
+        tree.mut_top().remove_child(0);
+        let options = DotOptions::new(|node: Ref<String>|node.len().to_string())
+            .node_attributes(|_|String::from("shape=box"))
+            .edge_attributes(|parent, _|format!("label={}", *parent))
+            .show_indices(true)
+            .show_free_list(true);
+        let dot = tree.to_dot_with(&options);
+        assert!(dot.contains("    n2 [label=\"2: 3\", shape=box];\n    n0 -> n2 [label=a];\n"));
+        assert!(dot.contains("    free -> free1 [style=dashed];\n"));
+        assert!(!Tree::new(String::new()).to_dot_with(&options).contains("free"));
+    }
+
+    #[test]
+    fn test_newick() {
+        use crate::newick::{NewickNode, NewickErrorKind};
+        use crate::reference::TreeRef;
+
+        let tree = Tree::from_newick("((A:0.1,B_1:0.2)'it''s',[comment] C:1e-3, D)E;").unwrap();
+        assert_eq!(NewickNode{ name: String::from("it's"), branch_length: None }, *tree.top().get_child(0));
+        assert_eq!("B 1", tree.top().get_child(0).get_child(1).name);
+        assert_eq!(Some(0.001), tree.top().get_child(1).branch_length);
+        assert_eq!(3, tree.top().children_count());
+        assert_eq!("((A:0.1,'B 1':0.2)'it''s',C:0.001,D)E;", tree.to_newick());
+
+        let names = Tree::from_newick_with("(A,(B,C)D);", |node|node.name).unwrap();
+        assert_eq!("(A, D(B, C))", names.to_string());
+        assert_eq!("(A,(B,C)D);", names.to_newick_with(|name|NewickNode{ name: name.clone(), branch_length: None }));
+
+        assert_eq!(NewickErrorKind::UnexpectedEnd, Tree::from_newick("(A,B)").unwrap_err().kind);
+        assert_eq!(NewickErrorKind::InvalidBranchLength, Tree::from_newick("(A:x);").unwrap_err().kind);
+        assert_eq!(4, Tree::from_newick("(A)B(C);").unwrap_err().position);
+
+        // writing does not recurse, so deep trees can be written back
+        let text = "(".repeat(100_000) + "A" + &")".repeat(100_000) + ";";
+        assert_eq!(text, Tree::from_newick(&text).unwrap().to_newick());
+    }
+
+    #[test]
+    fn test_json() {
+        use crate::json::{JsonNode, JsonValue, JsonErrorKind};
+        use crate::reference::TreeRef;
+
+        let text = r#" {"a": [1, -2.5e3, true, null], "b\n\u00e4\ud83d\ude00": {}, "c": {"d": "\"x\""}, "e": []} "#;
+        let tree = Tree::from_json(text).unwrap();
+        assert_eq!(JsonNode{ key: Some(String::from("b\nä😀")), value: JsonValue::Object }, *tree.top().get_child(1));
+        assert_eq!(JsonValue::Number(-2500.0), tree.get_index(3).unwrap().value);
+
+        let compact = r#"{"a":[1,-2500,true,null],"b\nä😀":{},"c":{"d":"\"x\""},"e":[]}"#;
+        assert_eq!(compact, tree.to_json());
+        assert_eq!(compact, Tree::from_json(&tree.to_json_pretty()).unwrap().to_json());
+        assert_eq!("[\n  1,\n  {\n    \"a\": 2\n  }\n]", Tree::from_json("[1,{\"a\":2}]").unwrap().to_json_pretty());
+        assert_eq!("3", Tree::from_json("3").unwrap().to_json());
+
+        for (text, kind, position) in &[("[1,]", JsonErrorKind::UnexpectedChar(']'), 3), ("01", JsonErrorKind::InvalidNumber, 0), ("{\"a\" 1}", JsonErrorKind::UnexpectedChar('1'), 5), ("[1", JsonErrorKind::UnexpectedEnd, 2), ("\"\\x\"", JsonErrorKind::InvalidEscape, 2), ("\"\\u123é\"", JsonErrorKind::InvalidEscape, 3), ("\"\\u12", JsonErrorKind::UnexpectedEnd, 3)] {
+            let error = Tree::from_json(text).unwrap_err();
+            assert_eq!((kind, *position), (&error.kind, error.position), "{}", text);
+        }
+    }
+
+    #[test]
+    fn test_sexpr() {
+        use crate::sexpr::{Atom, SexprErrorKind};
+        use crate::reference::TreeRef;
+
+        let text = "(define ; comment\n  (square x) (* x x 1.0 -2 \"a \\\"b\\\"\") inf)";
+        let tree = Tree::<Atom>::from_sexpr(text).unwrap();
+        assert_eq!(Atom::Symbol(String::from("define")), *tree.top());
+        assert_eq!(Atom::Float(1.0), *tree.get_index(6).unwrap());
+        assert_eq!(Atom::Integer(-2), *tree.get_index(7).unwrap());
+        assert_eq!(Atom::String(String::from("a \"b\"")), *tree.get_index(8).unwrap());
+        assert_eq!(Atom::Symbol(String::from("inf")), *tree.get_index(9).unwrap());
+        assert_eq!("(define (square x) (* x x 1.0 -2 \"a \\\"b\\\"\") inf)", tree.to_sexpr());
+
+        let tree = Tree::<String>::from_sexpr("(a \"b c\" (d))").unwrap();
+        assert_eq!("a(b c, d)", tree.to_string());
+        assert_eq!("(a \"b c\" d)", tree.to_sexpr());
+        let error = Tree::<u32>::from_sexpr("(1 x)").unwrap_err();
+        assert!(matches!(error.kind, SexprErrorKind::InvalidAtom(_)));
+        assert_eq!(3, error.position);
+
+        assert_eq!(SexprErrorKind::MissingHead, Tree::<Atom>::from_sexpr("((a) b)").unwrap_err().kind);
+        assert_eq!(SexprErrorKind::UnexpectedEnd, Tree::<Atom>::from_sexpr("(a (b)").unwrap_err().kind);
+        assert_eq!(SexprErrorKind::UnexpectedChar('b'), Tree::<Atom>::from_sexpr("a b").unwrap_err().kind);
+
+        // every atom is read back as itself
+        let symbol = |text: &str|Atom::Symbol(String::from(text));
+        let atoms = vec![symbol("a b"), symbol(""), symbol("12"), symbol("|x\\"), symbol("+inf.0"), symbol("inf"), Atom::String(String::from("a\n\"b\"")), Atom::Integer(-3), Atom::Float(2.0), Atom::Float(-0.5), Atom::Float(f64::INFINITY), Atom::Float(f64::NEG_INFINITY), Atom::Float(f64::NAN)];
+        let mut tree = Tree::new(symbol("h"));
+        for atom in &atoms {
+            tree.mut_top().add_child(atom.clone());
+        }
+        let text = tree.to_sexpr();
+        assert!(text.starts_with("(h |a b| || |12| |\\|x\\\\| |+inf.0| inf "), "{}", text);
+        let parsed = Tree::<Atom>::from_sexpr(&text).unwrap();
+        assert_eq!(atoms.len(), parsed.top().children().count());
+        for (atom, parsed) in atoms.iter().zip(parsed.top().children()) {
+            match (atom, &*parsed) {
+                (Atom::Float(a), Atom::Float(b)) if a.is_nan() => assert!(b.is_nan()),
+                (atom, parsed) => assert_eq!(atom, parsed),
+            }
+        }
+        assert_eq!("\"|x\"", Tree::<String>::from_sexpr("\"|x\"").unwrap().to_sexpr());
+        assert_eq!(SexprErrorKind::UnexpectedEnd, Tree::<Atom>::from_sexpr("(a |b)").unwrap_err().kind);
+    }
+
+    #[test]
+    fn test_binary() {
+        use crate::binary::BinaryError;
+
+        let mut tree: Tree<String> = "a(b(c, d), e, f(g))".parse().unwrap();
+        tree.mut_top().remove_child(1);
+        let bytes = tree.to_bytes();
+        assert_eq!(b"PTRE\x01\x00\x06\x00\x00\x00\x02\x02\x00\x00\x01\x00", &bytes[..16]);
+
+        let decoded = Tree::<String>::read_binary(&mut &bytes[..]).unwrap();
+        assert_eq!("a(b(c, d), f(g))", decoded.to_string());
+        assert_eq!(6, decoded.capacity());
+
+        let mut corrupt = bytes.clone();
+        corrupt[20] ^= 1;
+        assert!(matches!(Tree::<String>::from_bytes(&corrupt), Err(BinaryError::ChecksumMismatch)));
+        assert!(matches!(Tree::<String>::from_bytes(&bytes[..bytes.len() - 1]), Err(BinaryError::ChecksumMismatch)));
+        assert!(matches!(Tree::<String>::from_bytes(b"TREE"), Err(BinaryError::InvalidMagic)));
+
+        let large = (1..300u32).fold(Tree::new(0u32), |mut tree, value|{
+            tree.mut_top().add_child(value);
+            tree
+        });
+        assert_eq!(large.to_string(), Tree::<u32>::from_bytes(&large.to_bytes()).unwrap().to_string());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        use serde::{Serialize, Deserialize};
+
+        let mut tree: Tree<String> = "a(b(c), d, e)".parse().unwrap();
+        tree.mut_top().remove_child(1);
+        let json = serde_json::to_string(&tree).unwrap();
+        assert_eq!(r#"{"value":"a","children":[{"value":"b","children":[{"value":"c","children":[]}]},{"value":"e","children":[]}]}"#, json);
+        let nested: Tree<String> = serde_json::from_str(r#"{"value":"a","children":[{"value":"b"}]}"#).unwrap();
+        assert_eq!("a(b)", nested.to_string());
+
+        #[derive(Serialize, Deserialize)]
+        struct Saved {
+            #[serde(with = "crate::serialize::flat")]
+            tree: Tree<String>,
+        }
+
+        let json = serde_json::to_string(&Saved{ tree }).unwrap();
+        assert_eq!(r#"{"tree":[{"value":"a","parent":null,"position":0},{"value":"b","parent":0,"position":0},{"value":"c","parent":1,"position":0},null,{"value":"e","parent":0,"position":1}]}"#, json);
+        let mut loaded: Saved = serde_json::from_str(&json).unwrap();
+        assert_eq!("a(b(c), e)", loaded.tree.to_string());
+        assert_eq!("e", *loaded.tree.get_index(4).unwrap());
+        loaded.tree.mut_top().add_child("f".to_string());
+        assert_eq!("f", *loaded.tree.get_index(3).unwrap());
+
+        assert!(serde_json::from_str::<Saved>(r#"{"tree":[{"value":"a","parent":null,"position":0},{"value":"b","parent":2,"position":0},{"value":"c","parent":1,"position":0}]}"#).is_err());
+        assert!(serde_json::from_str::<Saved>(r#"{"tree":[{"value":"a","parent":null,"position":0},{"value":"b","parent":0,"position":1}]}"#).is_err());
+        assert!(serde_json::from_str::<Saved>(r#"{"tree":[null]}"#).is_err());
+    }
+
+    #[test]
+    fn test_parent_array() {
+        use crate::structure::StructureError;
+
+        let values = vec!["b", "a", "d", "c", "e"];
+        let tree = Tree::from_parent_array(values.clone(), &[Some(1), None, Some(1), Some(0), Some(1)]).unwrap();
+        assert_eq!("a(b(c), d, e)", tree.to_string());
+        let (values, parents) = tree.to_parent_array();
+        assert_eq!(vec![&"a", &"b", &"c", &"d", &"e"], values);
+        assert_eq!(vec![None, Some(0), Some(1), Some(0), Some(0)], parents);
+        let (_, edges) = tree.to_edge_list();
+        assert_eq!(vec![(0, 1), (1, 2), (0, 3), (0, 4)], edges);
+
+        let tree = Tree::from_edges(vec!["b", "a", "c"], &[(1, 2), (1, 0)]).unwrap();
+        assert_eq!("a(b, c)", tree.to_string());
+
+        assert_eq!(Some(StructureError::LengthMismatch{ values: 2, parents: 1 }), Tree::from_parent_array(vec![1, 2], &[None]).err());
+        assert_eq!(Some(StructureError::NoRoot), Tree::from_parent_array(vec![1, 2], &[Some(1), Some(0)]).err());
+        assert_eq!(Some(StructureError::MultipleRoots(0, 2)), Tree::from_parent_array(vec![1, 2, 3], &[None, Some(0), None]).err());
+        assert_eq!(Some(StructureError::DanglingParent{ node: 1, parent: 5 }), Tree::from_parent_array(vec![1, 2], &[None, Some(5)]).err());
+        assert_eq!(Some(StructureError::Cycle(1)), Tree::from_parent_array(vec![1, 2, 3, 4], &[None, Some(2), Some(1), Some(2)]).err());
+        assert_eq!(Some(StructureError::MultipleParents(2)), Tree::from_edges(vec![1, 2, 3], &[(0, 2), (1, 2)]).err());
+        assert_eq!(Some(StructureError::NodeOutOfRange(3)), Tree::from_edges(vec![1, 2, 3], &[(0, 3)]).err());
+    }
+
+    #[test]
+    fn test_from_dir() {
+        use crate::fs::{DirOptions, FsKind};
+        use crate::reference::TreeRef;
+
+        // removes the directory even if an assertion fails
+        struct TempDir(std::path::PathBuf);
+        impl Drop for TempDir {
+            fn drop(&mut self) {
+                let _ = std::fs::remove_dir_all(&self.0);
+            }
+        }
+
+        let dir = TempDir(std::env::temp_dir().join(format!("pool_tree_test_{}", std::process::id())));
+        let root = &dir.0;
+        std::fs::create_dir_all(root.join("b").join("d")).unwrap();
+        std::fs::write(root.join("a.txt"), "hello").unwrap();
+        std::fs::write(root.join("b").join("c.rs"), "").unwrap();
+        std::fs::write(root.join("b").join("d").join("e.tmp"), "").unwrap();
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(root, root.join("b").join("up")).unwrap();
+
+        let name = root.file_name().unwrap().to_str().unwrap().to_string();
+        let (tree, errors) = Tree::from_dir(root, &DirOptions::default()).unwrap();
+        assert!(errors.is_empty());
+        assert_eq!(FsKind::File, tree.top().get_child(0).kind);
+        assert_eq!(5, tree.top().get_child(0).size);
+        if cfg!(unix) {
+            assert_eq!(format!("{}(a.txt, b(c.rs, d(e.tmp), up))", name), tree.to_string());
+            assert_eq!(FsKind::Symlink, tree.top().get_child(1).get_child(2).kind);
+        }
+
+        let options = DirOptions{ max_depth: Some(2), ignore: vec!["*.r?".to_string(), "up".to_string()], ..DirOptions::default() };
+        let (tree, _) = Tree::from_dir(root, &options).unwrap();
+        assert_eq!(format!("{}(a.txt, b(d))", name), tree.to_string());
+
+        #[cfg(unix)]
+        {
+            let options = DirOptions{ follow_symlinks: true, ..DirOptions::default() };
+            assert!(Tree::from_dir(root, &options).is_err());
+            let options = DirOptions{ follow_symlinks: true, collect_errors: true, ..DirOptions::default() };
+            let (tree, errors) = Tree::from_dir(root, &options).unwrap();
+            assert_eq!(1, errors.len());
+            assert_eq!(root.join("b").join("up"), errors[0].path);
+            assert_eq!(FsKind::Directory, tree.top().get_child(1).get_child(2).kind);
+            assert_eq!(0, tree.top().get_child(1).get_child(2).children_count());
+        }
+    }
+
+    #[test]
+    fn test_tree_macro() {
+        use crate::reference::TreeRef;
+
+        let tree = tree![1 => [2, 3 => [4, 5 => []], 6,]];
+        assert_eq!("1(2, 3(4, 5), 6)", tree.to_string());
+        assert_eq!("7", tree![7].to_string());
+
+        let tree = tree![exact; String::from("a") => [String::from("b") => [String::from("c")], String::from("d")]];
+        assert_eq!("a(b(c), d)", tree.to_string());
+        assert_eq!(4, tree.capacity());
+        assert_eq!(1, tree![exact; 0].capacity());
+
+        // the siblings are matched at once, so wide nodes do not hit the recursion limit
+        macro_rules! wide {
+            ($($form: ident)?; $($value: tt)*) => {
+                tree![$($form;)? 0 => [$($value, $value, $value, $value, $value, $value, $value, $value, $value, $value,)* 15 => [16]]]
+            };
+        }
+        let tree = wide![; 1 2 3 4 5 6 7 8 9 10 11 12 13 14];
+        assert_eq!(141, tree.top().children_count());
+        assert_eq!(16, *tree.top().get_child(140).get_child(0));
+        assert_eq!(143, wide![exact; 1 2 3 4 5 6 7 8 9 10 11 12 13 14].capacity());
+    }
+
+    #[test]
+    fn test_builder() {
+        use crate::builder::{TreeBuilder, BuildError, BuildErrorKind};
+
+        let mut builder = TreeBuilder::with_capacity(5);
+        builder.open("a").open("b").leaf("c").close();
+        assert_eq!(1, builder.depth());
+        builder.leaf("d").open("e").close().close();
+        let tree = builder.finish().unwrap();
+        assert_eq!("a(b(c), d, e)", tree.to_string());
+        assert_eq!(5, tree.capacity());
+
+        let events = |build: &dyn Fn(&mut TreeBuilder<u32>)|{
+            let mut builder = TreeBuilder::new();
+            build(&mut builder);
+            builder.finish()
+        };
+        assert_eq!("1", events(&|builder|{builder.leaf(1);}).unwrap().to_string());
+        assert_eq!(Some(BuildError{ event: 0, kind: BuildErrorKind::Empty }), events(&|_|{}).err());
+        assert_eq!(Some(BuildError{ event: 2, kind: BuildErrorKind::Unclosed(1) }), events(&|builder|{builder.open(1).leaf(2);}).err());
+        assert_eq!(Some(BuildError{ event: 2, kind: BuildErrorKind::UnexpectedClose }), events(&|builder|{builder.open(1).close().close().open(2);}).err());
+        assert_eq!(Some(BuildError{ event: 1, kind: BuildErrorKind::MultipleRoots }), events(&|builder|{builder.leaf(1).leaf(2);}).err());
+    }
+
+    #[test]
+    fn test_unfold() {
+        use crate::unfold::UnfoldOptions;
+
+        let heap = |n: u32|(n, if n < 4 { vec![2 * n, 2 * n + 1] } else { vec![] });
+        assert_eq!("1(2(4, 5), 3(6, 7))", Tree::unfold(1, heap).to_string());
+        assert_eq!("1(2(4, 5), 3(6, 7))", Tree::unfold_breadth_first(1, heap).to_string());
+
+        let options = UnfoldOptions{ max_nodes: Some(4), ..UnfoldOptions::default() };
+        assert_eq!("1(2(4, 5))", Tree::unfold_with(1, &options, heap).to_string());
+        let options = UnfoldOptions{ breadth_first: true, max_nodes: Some(4), ..UnfoldOptions::default() };
+        assert_eq!("1(2(4), 3)", Tree::unfold_with(1, &options, heap).to_string());
+        let options = UnfoldOptions{ max_depth: Some(1), ..UnfoldOptions::default() };
+        assert_eq!("1(2, 3)", Tree::unfold_with(1, &options, heap).to_string());
+
+        // the seeds of an infinite tree are only consumed up to the limits
+        let options = UnfoldOptions{ max_depth: Some(2), max_nodes: Some(6), ..UnfoldOptions::default() };
+        let tree = Tree::unfold_with(0u32, &options, |n|(n, (0..).map(move |i|n * 10 + i + 1)));
+        assert_eq!("0(1(11, 12, 13, 14))", tree.to_string());
+    }
+
+    #[test]
+    fn test_collect() {
+        let mut tree = tree![1 => [2 => [3]]];
+        tree.mut_top().add_children(vec![4, 5]);
+        tree.mut_top().get_child_unique(0).get_children_unique().extend(6..8);
+        assert_eq!("1(2(3, 6, 7), 4, 5)", tree.to_string());
+
+        let nodes: Vec<(usize, u32)> = tree.into_iter().collect();
+        assert_eq!(vec![(0, 1), (1, 2), (2, 3), (2, 6), (2, 7), (1, 4), (1, 5)], nodes);
+        let tree: Tree<u32> = nodes.into_iter().collect();
+        assert_eq!("1(2(3, 6, 7), 4, 5)", tree.to_string());
+
+        // values which are not yielded are dropped with the iterator
+        use std::rc::Rc;
+        let value = Rc::new(());
+        let tree: Tree<Rc<()>> = vec![(0, value.clone()), (1, value.clone()), (2, value.clone())].into_iter().collect();
+        let mut iter = tree.into_iter();
+        iter.next();
+        assert_eq!(3, Rc::strong_count(&value));
+        drop(iter);
+        assert_eq!(1, Rc::strong_count(&value));
+    }
+
+    #[test]
+    fn test_map() {
+        let mut tree: Tree<String> = "1(2(3), 4, 5)".parse().unwrap();
+        tree.mut_top().remove_child(1);
+        tree.mut_top().get_child_unique(0).remove_child(0);
+
+        let depths = tree.map_ref(|node|node.pre_order().count());
+        assert_eq!("3(1, 1)", depths.to_string());
+        let tree = tree.map(|value|value.parse::<u32>().unwrap());
+        assert_eq!("1(2, 5)", tree.to_string());
+        assert_eq!(5, *tree.get_index(4).unwrap());
+        assert_eq!(1, *depths.get_index(4).unwrap());
+        assert!(tree.get_index(2).is_none() && tree.get_index(3).is_none());
+        let pool = format!("{:?}", tree.debug_pool());
+        let tree = tree.map(|value|value);
+        assert_eq!(pool, format!("{:?}", tree.debug_pool()));
+
+        let mut tree = tree.map(|value|value * 10);
+
+        assert_eq!(Err(50), tree.try_map(|value|if *value < 50 { Ok(value + 1) } else { Err(*value) }));
+        assert_eq!("10(20, 50)", tree.to_string());
+        assert_eq!(Ok(()), tree.try_map(|value|Ok::<_, ()>(value + 1)));
+        assert_eq!("11(21, 51)", tree.to_string());
+    }
+
+    #[test]
+    fn test_fold() {
+        use crate::reference::TreeRef;
+        use crate::ref_mut::TreeRefMut;
+
+        let mut tree = tree![1 => [2 => [3, 4], 5]];
+        assert_eq!(15, tree.top().fold(|value, children: &[u32]|value + children.iter().sum::<u32>()));
+        assert_eq!(3, tree.top().fold(|_, children: &[usize]|1 + children.iter().max().copied().unwrap_or(0)));
+        assert_eq!("2(3, 4)", tree.top().get_child(0).fold(|value, children: &[String]|{
+            if children.is_empty() { value.to_string() } else { format!("{}({})", value, children.join(", ")) }
+        }));
+
+        tree.mut_top().propagate_down(0, |sum, value|{
+            *value += sum;
+            *value
+        });
+        assert_eq!("1(3(6, 7), 6)", tree.to_string());
+        tree.mut_top().get_child_unique(0).propagate_down(100, |depth, value|{
+            *value = *depth;
+            depth + 1
+        });
+        assert_eq!("1(100(101, 101), 6)", tree.to_string());
+    }
+
+    #[test]
+    fn test_visitor() {
+        use crate::visitor::{Visitor, VisitorMut, VisitControl, walk, walk_mut};
+        use crate::reference::{Ref, TreeRef};
+        use crate::ref_mut::RefMut;
+
+        struct Log(Vec<String>);
+
+        impl Visitor<&'static str> for Log {
+            fn enter(&mut self, node: Ref<&'static str>) -> VisitControl {
+                self.0.push(format!("enter {}", *node));
+                if *node == "c" { VisitControl::SkipChildren } else { VisitControl::Continue }
+            }
+
+            fn leave(&mut self, node: Ref<&'static str>) -> VisitControl {
+                self.0.push(format!("leave {}", *node));
+                VisitControl::Continue
+            }
+
+            fn visit_leaf(&mut self, node: Ref<&'static str>) -> VisitControl {
+                self.0.push(format!("leaf {}", *node));
+                if *node == "f" { VisitControl::Stop } else { VisitControl::Continue }
+            }
+        }
+
+        let tree = tree!["a" => ["b" => ["x"], "c" => ["d"], "e", "f", "g"]];
+        let mut log = Log(Vec::new());
+        assert_eq!(VisitControl::Stop, walk(&tree.top(), &mut log));
+        assert_eq!(vec!["enter a", "enter b", "leaf x", "leave b", "enter c", "leave c", "leaf e", "leaf f"], log.0);
+
+        let mut log = Log(Vec::new());
+        assert_eq!(VisitControl::Continue, walk(&tree.top().get_child(0), &mut log));
+        assert_eq!(VisitControl::Continue, walk(&Tree::new("c").top(), &mut log));
+        assert_eq!(vec!["enter b", "leaf x", "leave b", "leaf c"], log.0);
+
+        struct Depth(u32);
+
+        impl VisitorMut<u32> for Depth {
+            fn enter(&mut self, mut node: RefMut<u32>) -> VisitControl {
+                *node = self.0;
+                self.0 += 1;
+                VisitControl::Continue
+            }
+
+            fn leave(&mut self, _node: RefMut<u32>) -> VisitControl {
+                self.0 -= 1;
+                VisitControl::Continue
+            }
+
+            fn visit_leaf(&mut self, mut node: RefMut<u32>) -> VisitControl {
+                *node = self.0;
+                VisitControl::Continue
+            }
+        }
+
+        let mut tree = tree![9 => [9 => [9, 9], 9]];
+        walk_mut(&mut tree.mut_top(), &mut Depth(0));
+        assert_eq!("0(1(2, 2), 1)", tree.to_string());
+    }
+
+    #[test]
+    fn test_edges() {
+        use crate::iter::Edge;
+
+        let tree = tree!["html" => ["head" => ["title"], "body" => ["p", "p"]]];
+        let mut xml = String::new();
+        for edge in tree.top().edges() {
+            match edge {
+                Edge::Open(node) => xml.push_str(&format!("<{}>", *node)),
+                Edge::Close(node) => xml.push_str(&format!("</{}>", *node)),
+            }
+        }
+        assert_eq!("<html><head><title></title></head><body><p></p><p></p></body></html>", xml);
+        assert_eq!(2, Tree::new(0).top().edges().count());
+
+        // Display does not recurse, so deep trees can be printed
+        let tree: Tree<usize> = (0..100_000).map(|depth|(depth, depth)).collect();
+        let text = tree.to_string();
+        assert!(text.starts_with("0(1(2(") && text.contains("(99998(99999))") && text.ends_with(&")".repeat(99_999)));
+    }
+
+    #[test]
+    fn test_for_each_mut() {
+        use crate::reference::TreeRef;
+        use crate::ref_mut::TreeRefMut;
+
+        let mut tree = tree![1 => [2 => [3, 4], 5]];
+        tree.mut_top().for_each_mut(|mut node|*node *= 10);
+        assert_eq!("10(20(30, 40), 50)", tree.to_string());
+
+        let mut order = Vec::new();
+        let mut top = tree.mut_top();
+        let mut child = top.get_child_mut(0);
+        let mut nodes = child.pre_order_mut();
+        while let Some(mut node) = nodes.next_mut() {
+            order.push(*node);
+            *node += node.children_count();
+        }
+        assert_eq!(vec![20, 30, 40], order);
+        assert_eq!("10(22(30, 40), 50)", tree.to_string());
+    }
+
 }
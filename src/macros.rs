@@ -0,0 +1,46 @@
+/// creates a Tree from a nested list of values. A node with children is written as
+/// `value => [children]`, the children are separated by commas.
+///
+/// ```
+/// use pool_tree::tree;
+///
+/// let tree = tree![1 => [2, 3 => [4, 5]]];
+/// assert_eq!("1(2, 3(4, 5))", tree.to_string());
+/// ```
+///
+/// Starting with `exact;` counts the nodes and allocates the buffer of the Tree once with the
+/// exact size, like `tree![exact; 1 => [2, 3]]`.
+#[macro_export]
+macro_rules! tree {
+    (exact; $root: expr $(=> [$($children: tt)*])?) => {{
+        let capacity = 1 $(+ $crate::tree!(@count $($children)*))?;
+        #[allow(unused_mut)]
+        let mut tree = $crate::tree::Tree::with_capacity($root, capacity);
+        $({
+            #[allow(unused_mut, unused_variables)]
+            let mut node = tree.mut_top();
+            $crate::tree!(@children node; $($children)*);
+        })?
+        tree
+    }};
+    (@children $parent: ident; $($value: expr $(=> [$($children: tt)*])?),* $(,)?) => {
+        $({
+            #[allow(unused_mut, unused_variables)]
+            let mut node = $parent.add_child($value);
+            $($crate::tree!(@children node; $($children)*);)?
+        })*
+    };
+    (@count $($value: expr $(=> [$($children: tt)*])?),* $(,)?) => {
+        0usize $(+ 1 $(+ $crate::tree!(@count $($children)*))?)*
+    };
+    ($root: expr $(=> [$($children: tt)*])?) => {{
+        #[allow(unused_mut)]
+        let mut tree = $crate::tree::Tree::new($root);
+        $({
+            #[allow(unused_mut, unused_variables)]
+            let mut node = tree.mut_top();
+            $crate::tree!(@children node; $($children)*);
+        })?
+        tree
+    }};
+}
@@ -0,0 +1,48 @@
+use crate::tree::Tree;
+use crate::reference::Ref;
+
+impl<T: 'static> Tree<T> {
+    /// converts every value with map. The result has the same layout as this Tree, every node keeps
+    /// its index and the unused Elements keep their order, so indices stay valid.
+    pub fn map<U: 'static>(mut self, mut map: impl FnMut(T) -> U) -> Tree<U> {
+        let buffer = (0..self.pool_len() as u32)
+            .map(|index|unsafe {self.get_raw_mut(index)}.take_with_layout(&mut map))
+            .collect();
+        unsafe {Tree::from_raw_parts(buffer, self.first_free())}
+    }
+
+    /// converts every node with map, which may access the surrounding nodes. The result has the
+    /// same layout as this Tree like [`Tree::map`]. The nodes are visited in the order of their
+    /// indices.
+    pub fn map_ref<U: 'static>(&self, mut map: impl FnMut(Ref<T>) -> U) -> Tree<U> {
+        let buffer = (0..self.pool_len() as u32).map(|index|{
+            let element = unsafe {self.get_raw(index)};
+            let value = if element.is_used() {
+                Some(map(unsafe {self.get_unchecked(index)}))
+            } else {
+                None
+            };
+            element.with_layout(value)
+        }).collect();
+        unsafe {Tree::from_raw_parts(buffer, self.first_free())}
+    }
+
+    /// replaces every value with the result of map. If map fails for any node, the error is
+    /// returned and the Tree is left unchanged. The nodes are visited in the order of their
+    /// indices.
+    pub fn try_map<E>(&mut self, mut map: impl FnMut(&T) -> Result<T, E>) -> Result<(), E> {
+        let mut values = Vec::with_capacity(self.pool_len());
+        for index in 0..self.pool_len() as u32 {
+            values.push(match unsafe {self.get_raw(index)}.get_value_checked() {
+                Some(value) => Some(map(value)?),
+                None => None,
+            });
+        }
+        for (index, value) in values.into_iter().enumerate() {
+            if let Some(value) = value {
+                *unsafe {self.get_raw_mut(index as u32).get_value_mut()} = value;
+            }
+        }
+        Ok(())
+    }
+}
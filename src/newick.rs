@@ -0,0 +1,254 @@
+use std::fmt::{Display, Debug, Formatter, Write};
+use std::error::Error;
+use crate::tree::Tree;
+use crate::reference::TreeRef;
+use crate::iter::Edge;
+
+/// A node of a phylogenetic tree in the Newick format
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct NewickNode {
+    /// the label of the node, empty if the node has none
+    pub name: String,
+    /// the length of the branch to the parent
+    pub branch_length: Option<f64>,
+}
+
+/// The error returned when parsing the Newick format
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct NewickError {
+    /// the byte offset in the input at which the error occurred
+    pub position: usize,
+    pub kind: NewickErrorKind,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum NewickErrorKind {
+    /// the input ended before the terminating `;`
+    UnexpectedEnd,
+    /// the character is not allowed at this position
+    UnexpectedChar(char),
+    /// the branch length starting at position is no valid number
+    InvalidBranchLength,
+    /// a quoted label is not closed
+    UnterminatedQuote,
+    /// a comment in square brackets is not closed
+    UnterminatedComment,
+}
+
+impl Display for NewickError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match &self.kind {
+            NewickErrorKind::UnexpectedEnd => write!(f, "unexpected end of input at {}", self.position),
+            NewickErrorKind::UnexpectedChar(c) => write!(f, "unexpected character {:?} at {}", c, self.position),
+            NewickErrorKind::InvalidBranchLength => write!(f, "invalid branch length at {}", self.position),
+            NewickErrorKind::UnterminatedQuote => write!(f, "unterminated quoted label starting at {}", self.position),
+            NewickErrorKind::UnterminatedComment => write!(f, "unterminated comment starting at {}", self.position),
+        }
+    }
+}
+
+impl Error for NewickError {}
+
+impl Tree<NewickNode> {
+    /// parses a single tree in the Newick format, like `(A:0.1,B:0.2)C;`
+    pub fn from_newick(text: &str) -> Result<Self, NewickError> {
+        Tree::from_newick_with(text, |node|node)
+    }
+
+    /// writes the Tree in the Newick format
+    pub fn to_newick(&self) -> String {
+        self.to_newick_with(|node|node.clone())
+    }
+}
+
+impl<T: 'static> Tree<T> {
+    /// parses a single tree in the Newick format and converts every node with map.
+    ///
+    /// Unquoted labels end at whitespace or one of `()[]':;,` and underscores are replaced by
+    /// spaces. Quoted labels are enclosed in `'`, a quote inside of them is written as `''`.
+    /// Comments in square brackets are skipped.
+    pub fn from_newick_with(text: &str, mut map: impl FnMut(NewickNode) -> T) -> Result<Self, NewickError> {
+        let nodes = Parser{ text, position: 0 }.parse()?;
+
+        let mut nodes = nodes.into_iter();
+        let (root, _) = nodes.next().expect("the parser returns at least the root");
+        let mut tree = Tree::new(map(root));
+        // nodes are in pre-order, therefore the parent is always inserted before its children
+        let mut indices = vec![0];
+        for (node, parent) in nodes {
+            let index = unsafe {tree.push_child(indices[parent], map(node))};
+            indices.push(index.get());
+        }
+        Ok(tree)
+    }
+
+    /// writes the Tree in the Newick format, map returns the label and branch length of a node
+    pub fn to_newick_with(&self, map: impl FnMut(&T) -> NewickNode) -> String {
+        let mut out = String::new();
+        write_newick(&self.top(), &mut out, map).unwrap();
+        out
+    }
+}
+
+/// writes the subtree of node in the Newick format including the terminating `;`.
+/// map returns the label and branch length of a node.
+pub fn write_newick<R: TreeRef>(node: &R, out: &mut impl Write, mut map: impl FnMut(&R::Type) -> NewickNode) -> std::fmt::Result {
+    write_node(node, out, &mut map)?;
+    out.write_char(';')
+}
+
+fn write_node<R: TreeRef, W: Write, F: FnMut(&R::Type) -> NewickNode>(node: &R, out: &mut W, map: &mut F) -> std::fmt::Result {
+    // a node after a sibling is separated by a comma
+    let mut separate = false;
+    for edge in node.get_ref().edges() {
+        match edge {
+            Edge::Open(node) => {
+                if separate {
+                    out.write_char(',')?;
+                }
+                separate = false;
+                if node.children_count() > 0 {
+                    out.write_char('(')?;
+                }
+            }
+            Edge::Close(node) => {
+                if node.children_count() > 0 {
+                    out.write_char(')')?;
+                }
+                write_label(out, map(&node))?;
+                separate = true;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn write_label(out: &mut impl Write, node: NewickNode) -> std::fmt::Result {
+    let NewickNode{ name, branch_length } = node;
+    if name.chars().any(|c|c.is_whitespace() || "()[]':;,_".contains(c)) {
+        out.write_char('\'')?;
+        out.write_str(&name.replace('\'', "''"))?;
+        out.write_char('\'')?;
+    } else {
+        out.write_str(&name)?;
+    }
+    if let Some(length) = branch_length {
+        write!(out, ":{}", length)?;
+    }
+    Ok(())
+}
+
+struct Parser<'a> {
+    text: &'a str,
+    position: usize,
+}
+
+impl<'a> Parser<'a> {
+    /// returns the nodes in pre-order with the position of their parent
+    fn parse(mut self) -> Result<Vec<(NewickNode, usize)>, NewickError> {
+        let mut nodes = vec![(NewickNode::default(), 0)];
+        // the nodes whose parentheses are currently open
+        let mut open: Vec<usize> = Vec::new();
+        let mut current = 0;
+        let mut closed = false;
+
+        loop {
+            self.skip()?;
+            if !closed && self.peek() == Some('(') {
+                self.position += 1;
+                open.push(current);
+                nodes.push((NewickNode::default(), current));
+                current = nodes.len() - 1;
+                continue;
+            }
+
+            nodes[current].0.name = self.label()?;
+            self.skip()?;
+            if self.peek() == Some(':') {
+                self.position += 1;
+                self.skip()?;
+                nodes[current].0.branch_length = Some(self.branch_length()?);
+                self.skip()?;
+            }
+
+            let position = self.position;
+            match self.peek() {
+                Some(',') if !open.is_empty() => {
+                    nodes.push((NewickNode::default(), *open.last().unwrap()));
+                    current = nodes.len() - 1;
+                    closed = false;
+                }
+                Some(')') if !open.is_empty() => {
+                    current = open.pop().unwrap();
+                    closed = true;
+                }
+                Some(';') if open.is_empty() => {
+                    self.position += 1;
+                    self.skip()?;
+                    return match self.peek() {
+                        None => Ok(nodes),
+                        Some(c) => Err(self.error(NewickErrorKind::UnexpectedChar(c))),
+                    };
+                }
+                Some(c) => return Err(NewickError{ position, kind: NewickErrorKind::UnexpectedChar(c) }),
+                None => return Err(self.error(NewickErrorKind::UnexpectedEnd)),
+            }
+            self.position += 1;
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.text[self.position..].chars().next()
+    }
+
+    fn error(&self, kind: NewickErrorKind) -> NewickError {
+        NewickError{ position: self.position, kind }
+    }
+
+    /// skips whitespace and comments
+    fn skip(&mut self) -> Result<(), NewickError> {
+        loop {
+            match self.peek() {
+                Some(c) if c.is_whitespace() => self.position += c.len_utf8(),
+                Some('[') => {
+                    let end = self.text[self.position..].find(']').ok_or_else(||self.error(NewickErrorKind::UnterminatedComment))?;
+                    self.position += end + 1;
+                }
+                _ => return Ok(()),
+            }
+        }
+    }
+
+    fn label(&mut self) -> Result<String, NewickError> {
+        if self.peek() == Some('\'') {
+            let start = self.position;
+            let mut label = String::new();
+            self.position += 1;
+            loop {
+                let rest = &self.text[self.position..];
+                let end = rest.find('\'').ok_or(NewickError{ position: start, kind: NewickErrorKind::UnterminatedQuote })?;
+                label.push_str(&rest[..end]);
+                self.position += end + 1;
+                if self.peek() == Some('\'') {
+                    label.push('\'');
+                    self.position += 1;
+                } else {
+                    return Ok(label);
+                }
+            }
+        } else {
+            let rest = &self.text[self.position..];
+            let end = rest.find(|c: char|c.is_whitespace() || "()[]':;,".contains(c)).unwrap_or(rest.len());
+            self.position += end;
+            Ok(rest[..end].replace('_', " "))
+        }
+    }
+
+    fn branch_length(&mut self) -> Result<f64, NewickError> {
+        let rest = &self.text[self.position..];
+        let end = rest.find(|c: char|c.is_whitespace() || "()[]':;,".contains(c)).unwrap_or(rest.len());
+        let length = rest[..end].parse().map_err(|_|self.error(NewickErrorKind::InvalidBranchLength))?;
+        self.position += end;
+        Ok(length)
+    }
+}
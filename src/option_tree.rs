@@ -0,0 +1,107 @@
+use crate::tree::Tree;
+use crate::reference::Ref;
+use crate::ref_unique::RefUniq;
+use std::fmt::{Debug, Formatter};
+
+/// OptionTree is a Tree which may be empty. Unlike Option<Tree<T>> it keeps its buffer when the
+/// root is removed, so that the next root can reuse the allocated Elements.
+///
+/// If the tree is empty, the Element at index 0 is unused, otherwise it holds the root.
+pub struct OptionTree<T> {
+    tree: Tree<T>,
+}
+
+impl<T: 'static> OptionTree<T> {
+    /// creates a new empty OptionTree
+    pub fn new() -> Self {
+        OptionTree {
+            tree: Tree::without_root(),
+        }
+    }
+
+    /// returns true if the tree has no root
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.tree.get_index(0).is_none()
+    }
+
+    /// returns the root if the tree is not empty
+    #[inline]
+    pub fn root(&self) -> Option<Ref<T>> {
+        self.tree.get_index(0)
+    }
+
+    /// returns an unique reference to the root if the tree is not empty
+    #[inline]
+    pub fn root_mut(&mut self) -> Option<RefUniq<T>> {
+        self.tree.get_index_mut(0)
+    }
+
+    /// sets a new root without children. If the tree was not empty, the old nodes are dropped.
+    pub fn set_root(&mut self, value: T) -> RefUniq<T> {
+        self.take_root();
+        unsafe {
+            self.tree.get_raw_mut(0).set_used(value, 0);
+            self.tree.get_unchecked_mut(0)
+        }
+    }
+
+    /// removes all nodes and returns the value of the root, the buffer is kept for reuse.
+    /// Returns None if the tree was empty.
+    pub fn take_root(&mut self) -> Option<T> {
+        if self.is_empty() {
+            return None;
+        }
+        self.tree.clear_descendants();
+        unsafe {
+            Some(self.tree.get_raw_mut(0).set_unused(None))
+        }
+    }
+
+    /// replaces the value of the root and keeps its children. If the tree is empty, value
+    /// becomes the new root and None is returned.
+    pub fn replace_root(&mut self, value: T) -> Option<T> {
+        match self.root_mut() {
+            Some(mut root) => Some(std::mem::replace(&mut *root, value)),
+            None => {
+                self.set_root(value);
+                None
+            }
+        }
+    }
+
+    /// returns the number of Elements the buffer can hold without reallocating
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.tree.capacity()
+    }
+
+    /// converts the OptionTree into a Tree if it is not empty
+    pub fn into_tree(self) -> Option<Tree<T>> {
+        if self.is_empty() {
+            None
+        } else {
+            Some(self.tree)
+        }
+    }
+}
+
+impl<T: 'static> Default for OptionTree<T> {
+    fn default() -> Self {
+        OptionTree::new()
+    }
+}
+
+impl<T: 'static> From<Tree<T>> for OptionTree<T> {
+    fn from(tree: Tree<T>) -> Self {
+        OptionTree {
+            tree,
+        }
+    }
+}
+
+impl<T: Debug + 'static> Debug for OptionTree<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.root().fmt(f)
+    }
+}
@@ -0,0 +1,183 @@
+use std::fmt::{Display, Debug, Formatter, Write};
+use std::str::FromStr;
+use std::error::Error;
+use crate::tree::Tree;
+
+/// The options of [`Tree::to_outline_with`]
+#[derive(Clone, Debug)]
+pub struct OutlineOptions {
+    /// the string written once per level of depth, if box_drawing is false
+    pub indent: String,
+    /// draws the levels with `├── `, `└── ` and `│   ` like the tree command
+    pub box_drawing: bool,
+}
+
+impl Default for OutlineOptions {
+    fn default() -> Self {
+        OutlineOptions {
+            indent: String::from("    "),
+            box_drawing: false,
+        }
+    }
+}
+
+/// The error returned by [`Tree::from_outline`]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct OutlineError<E> {
+    /// the line in which the error occurred, starting at 1
+    pub line: usize,
+    pub kind: OutlineErrorKind<E>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum OutlineErrorKind<E> {
+    /// the text contains no node
+    Empty,
+    /// a second line without indentation was found
+    MultipleRoots,
+    /// the indentation mixes tabs and spaces or is no multiple of the detected indentation
+    InconsistentIndentation,
+    /// the line is indented more than one level deeper than the previous line
+    UnexpectedIndentation,
+    /// the value of the line could not be parsed
+    InvalidValue(E),
+}
+
+impl<E: Display> Display for OutlineError<E> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: ", self.line)?;
+        match &self.kind {
+            OutlineErrorKind::Empty => f.write_str("the outline contains no node"),
+            OutlineErrorKind::MultipleRoots => f.write_str("found a second root"),
+            OutlineErrorKind::InconsistentIndentation => f.write_str("inconsistent indentation"),
+            OutlineErrorKind::UnexpectedIndentation => f.write_str("indented more than one level deeper than the parent"),
+            OutlineErrorKind::InvalidValue(error) => write!(f, "invalid value: {}", error),
+        }
+    }
+}
+
+impl<E: Debug + Display> Error for OutlineError<E> {}
+
+impl<T: Display + 'static> Tree<T> {
+    /// writes the Tree with one node per line, indented by four spaces per level of depth
+    pub fn to_outline(&self) -> String {
+        self.to_outline_with(&OutlineOptions::default())
+    }
+
+    /// writes the Tree with one node per line, the depth is indicated as described by options.
+    /// Values are written with their Display implementation and should not contain line breaks.
+    pub fn to_outline_with(&self, options: &OutlineOptions) -> String {
+        let mut out = String::new();
+        // for every ancestor below the root, whether it is the last child of its parent
+        let mut last_childs: Vec<bool> = Vec::new();
+        let mut stack = vec![(0, 0, true)];
+
+        while let Some((index, depth, last)) = stack.pop() {
+            let element = unsafe {self.get_raw(index)};
+            if depth > 0 {
+                last_childs.truncate(depth - 1);
+                if options.box_drawing {
+                    for ancestor_last in &last_childs {
+                        out.push_str(if *ancestor_last { "    " } else { "│   " });
+                    }
+                    out.push_str(if last { "└── " } else { "├── " });
+                } else {
+                    for _ in 0..depth {
+                        out.push_str(&options.indent);
+                    }
+                }
+                last_childs.push(last);
+            }
+            writeln!(out, "{}", unsafe {element.get_value()}).unwrap();
+
+            let childs = element.childs();
+            stack.extend(childs.iter().enumerate().rev().map(|(position, child)|{
+                (child.get(), depth + 1, position + 1 == childs.len())
+            }));
+        }
+        out
+    }
+}
+
+impl<T: FromStr + 'static> Tree<T> {
+    /// reads a Tree with one node per line and the depth given by the indentation. The indentation
+    /// is either the box drawing of [`Tree::to_outline_with`] or tabs or spaces. For spaces the
+    /// width of one level is the indentation of the first indented line. Empty lines are skipped.
+    pub fn from_outline(text: &str) -> Result<Tree<T>, OutlineError<T::Err>> {
+        let box_drawing = text.lines().any(|line|line.trim_start().starts_with(&['├', '└', '│'][..]));
+        let mut unit: Option<(char, usize)> = None;
+        let mut tree: Option<Tree<T>> = None;
+        // the indices of the last node on every level of depth
+        let mut path: Vec<u32> = Vec::new();
+
+        for (line_index, line) in text.lines().enumerate() {
+            let line_number = line_index + 1;
+            let error = |kind|OutlineError{ line: line_number, kind };
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let (depth, content) = if box_drawing {
+                box_depth(line).ok_or_else(||error(OutlineErrorKind::InconsistentIndentation))?
+            } else {
+                let content = line.trim_start();
+                let indentation = &line[..line.len() - content.len()];
+                if indentation.is_empty() {
+                    (0, content)
+                } else {
+                    let (unit_char, unit_width) = *unit.get_or_insert_with(||{
+                        if indentation.starts_with('\t') { ('\t', 1) } else { (' ', indentation.len()) }
+                    });
+                    if indentation.chars().any(|c|c != unit_char) || indentation.len() % unit_width != 0 {
+                        return Err(error(OutlineErrorKind::InconsistentIndentation));
+                    }
+                    (indentation.len() / unit_width, content)
+                }
+            };
+
+            let value = content.trim_end().parse().map_err(|e|error(OutlineErrorKind::InvalidValue(e)))?;
+            match &mut tree {
+                None if depth == 0 => {
+                    tree = Some(Tree::new(value));
+                    path.push(0);
+                }
+                None => return Err(error(OutlineErrorKind::UnexpectedIndentation)),
+                Some(_) if depth == 0 => return Err(error(OutlineErrorKind::MultipleRoots)),
+                Some(_) if depth > path.len() => return Err(error(OutlineErrorKind::UnexpectedIndentation)),
+                Some(tree) => {
+                    path.truncate(depth);
+                    let index = unsafe {tree.push_child(path[depth - 1], value)};
+                    path.push(index.get());
+                }
+            }
+        }
+        tree.ok_or(OutlineError{ line: text.lines().count(), kind: OutlineErrorKind::Empty })
+    }
+}
+
+/// splits the box drawing prefix of line into groups of four characters and returns their number
+/// and the rest of the line
+fn box_depth(line: &str) -> Option<(usize, &str)> {
+    let mut depth = 0;
+    let mut rest = line;
+    loop {
+        let mut chars = rest.chars();
+        match chars.next() {
+            Some('├') | Some('└') => {
+                if chars.next() != Some('─') || chars.next() != Some('─') {
+                    return None;
+                }
+                return Some((depth + 1, chars.as_str().trim_start()));
+            }
+            Some(c) if c == '│' || c.is_whitespace() => {
+                if !(0..3).all(|_|chars.next().is_some_and(char::is_whitespace)) {
+                    return None;
+                }
+                depth += 1;
+                rest = chars.as_str();
+            }
+            _ if depth == 0 => return Some((0, rest)),
+            _ => return None,
+        }
+    }
+}
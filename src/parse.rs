@@ -0,0 +1,181 @@
+use std::str::{FromStr, CharIndices};
+use std::iter::Peekable;
+use std::fmt::{Display, Debug, Formatter, Write};
+use std::error::Error;
+use crate::tree::Tree;
+
+/// The error returned by the FromStr implementation of Tree
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ParseTreeError<E> {
+    /// the byte offset in the input at which the error occurred
+    pub position: usize,
+    pub kind: ParseTreeErrorKind<E>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ParseTreeErrorKind<E> {
+    /// the input ended inside of an escape sequence or before all parentheses were closed
+    UnexpectedEnd,
+    /// the character is not allowed at this position
+    UnexpectedChar(char),
+    /// the value starting at position could not be parsed
+    InvalidValue(E),
+}
+
+impl<E: Display> Display for ParseTreeError<E> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match &self.kind {
+            ParseTreeErrorKind::UnexpectedEnd => write!(f, "unexpected end of input at {}", self.position),
+            ParseTreeErrorKind::UnexpectedChar(c) => write!(f, "unexpected character {:?} at {}", c, self.position),
+            ParseTreeErrorKind::InvalidValue(error) => write!(f, "invalid value at {}: {}", self.position, error),
+        }
+    }
+}
+
+impl<E: Debug + Display> Error for ParseTreeError<E> {}
+
+/// Parses the format written by the Display implementation of Tree: `value(child, child(...))`.
+/// Whitespace around values is ignored, the characters `(`, `,`, `)` and `\` as well as leading
+/// and trailing whitespace of a value have to be escaped with a `\`.
+impl<T: FromStr + 'static> FromStr for Tree<T> {
+    type Err = ParseTreeError<T::Err>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser {
+            input: s,
+            chars: s.char_indices().peekable(),
+        };
+        let mut tree = Tree::new(parser.value()?);
+
+        // the nodes whose parentheses are currently open
+        let mut open: Vec<u32> = Vec::new();
+        // the last node, which may still get children
+        let mut current = Some(0);
+
+        loop {
+            parser.skip_whitespace();
+            let position = parser.position();
+            match parser.chars.next() {
+                None if open.is_empty() => return Ok(tree),
+                None => return Err(ParseTreeError{ position, kind: ParseTreeErrorKind::UnexpectedEnd }),
+                Some((_, '(')) if current.is_some() => {
+                    open.push(current.take().unwrap());
+                    let value = parser.value()?;
+                    current = Some(unsafe { tree.push_child(*open.last().unwrap(), value) }.get());
+                }
+                Some((_, ',')) if !open.is_empty() => {
+                    let value = parser.value()?;
+                    current = Some(unsafe { tree.push_child(*open.last().unwrap(), value) }.get());
+                }
+                Some((_, ')')) if !open.is_empty() => {
+                    open.pop();
+                    current = None;
+                }
+                Some((_, c)) => return Err(ParseTreeError{ position, kind: ParseTreeErrorKind::UnexpectedChar(c) }),
+            }
+        }
+    }
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    chars: Peekable<CharIndices<'a>>,
+}
+
+impl<'a> Parser<'a> {
+    fn position(&mut self) -> usize {
+        let end = self.input.len();
+        self.chars.peek().map_or(end, |(index, _)|*index)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.chars.peek().is_some_and(|(_, c)|c.is_whitespace()) {
+            self.chars.next();
+        }
+    }
+
+    /// reads and unescapes a value up to the next unescaped delimiter and parses it
+    fn value<T: FromStr>(&mut self) -> Result<T, ParseTreeError<T::Err>> {
+        self.skip_whitespace();
+        let start = self.position();
+        let mut text = String::new();
+        // the length of text without trailing unescaped whitespace
+        let mut keep = 0;
+
+        while let Some((_, c)) = self.chars.peek().copied() {
+            match c {
+                '(' | ',' | ')' => break,
+                '\\' => {
+                    self.chars.next();
+                    let position = self.position();
+                    let (_, escaped) = self.chars.next().ok_or(ParseTreeError{ position, kind: ParseTreeErrorKind::UnexpectedEnd })?;
+                    text.push(escaped);
+                    keep = text.len();
+                }
+                c => {
+                    self.chars.next();
+                    text.push(c);
+                    if !c.is_whitespace() {
+                        keep = text.len();
+                    }
+                }
+            }
+        }
+        text.truncate(keep);
+        text.parse().map_err(|error|ParseTreeError{ position: start, kind: ParseTreeErrorKind::InvalidValue(error) })
+    }
+}
+
+/// writes value with the width, precision and alternate flag of f and escapes the characters,
+/// which the parser would interpret as delimiters
+pub(crate) fn write_escaped<T: Display + ?Sized>(f: &mut Formatter<'_>, value: &T) -> std::fmt::Result {
+    let (alternate, width, precision) = (f.alternate(), f.width().unwrap_or(0), f.precision());
+    let mut out = Escaper{ out: f, first: true, pending: None };
+    match (alternate, precision) {
+        (false, None) => write!(out, "{:1$}", value, width)?,
+        (false, Some(precision)) => write!(out, "{:1$.2$}", value, width, precision)?,
+        (true, None) => write!(out, "{:#1$}", value, width)?,
+        (true, Some(precision)) => write!(out, "{:#1$.2$}", value, width, precision)?,
+    }
+    out.finish()
+}
+
+/// escapes the characters written through it. Whitespace is escaped at the start and the end of
+/// the value, so one whitespace character is held back until the next one is written.
+struct Escaper<'a, W: Write> {
+    out: &'a mut W,
+    first: bool,
+    pending: Option<char>,
+}
+
+impl<'a, W: Write> Escaper<'a, W> {
+    fn finish(self) -> std::fmt::Result {
+        match self.pending {
+            Some(c) => {
+                self.out.write_char('\\')?;
+                self.out.write_char(c)
+            }
+            None => Ok(()),
+        }
+    }
+}
+
+impl<'a, W: Write> Write for Escaper<'a, W> {
+    fn write_str(&mut self, s: &str) -> std::fmt::Result {
+        for c in s.chars() {
+            if let Some(pending) = self.pending.take() {
+                self.out.write_char(pending)?;
+            }
+            let first = std::mem::replace(&mut self.first, false);
+            if c.is_whitespace() && !first {
+                self.pending = Some(c);
+                continue;
+            }
+            if (first && c.is_whitespace()) || matches!(c, '(' | ',' | ')' | '\\') {
+                self.out.write_char('\\')?;
+            }
+            self.out.write_char(c)?;
+        }
+        Ok(())
+    }
+}
@@ -1,9 +1,10 @@
 use std::ops::{Receiver, DerefMut, Deref};
 use crate::tree::{Element, Tree};
 use std::marker::PhantomData;
-use crate::reference::{TreeRef, Ref};
-use crate::iter::ChildIter;
+use crate::reference::{TreeRef, Ref, DebugNode};
+use crate::iter::{ChildIter, PreOrderMut};
 use crate::children_mut::ChildrenMut;
+use std::fmt::{Debug, Formatter};
 
 pub struct RefMut<'a, T> {
     _p: PhantomData<&'a mut Tree<T>>,
@@ -119,10 +120,69 @@ impl<'a, T: 'static> TreeRefMut for RefMut<'a, T> {
             (value, self.children_mut())
         }
     }
+
+    fn get_mut(&mut self) -> RefMut<Self::Type> {
+        self.id()
+    }
 }
 
 pub trait TreeRefMut: TreeRef {
     fn children_mut(&mut self) -> ChildrenMut<Self::Type>;
     fn get_child_mut(&mut self, index: u32) -> RefMut<Self::Type>;
     fn both(&mut self) -> (&mut Self::Type, ChildrenMut<Self::Type>);
-}
\ No newline at end of file
+    /// returns a RefMut to the same node, by default with the Tree of [`TreeRefMut::children_mut`]
+    fn get_mut(&mut self) -> RefMut<Self::Type> {
+        let index = self.index();
+        let buffer = self.children_mut().buffer();
+        unsafe {
+            RefMut::create(index, buffer)
+        }
+    }
+
+    /// returns a cursor over this node and its descendants in pre-order, see [`PreOrderMut`]
+    fn pre_order_mut(&mut self) -> PreOrderMut<Self::Type> {
+        let node = self.get_mut();
+        unsafe {
+            PreOrderMut::new(node.buffer, node.index)
+        }
+    }
+
+    /// calls f with a RefMut to this node and each of its descendants in pre-order
+    fn for_each_mut(&mut self, mut f: impl FnMut(RefMut<Self::Type>)) {
+        let mut nodes = self.pre_order_mut();
+        while let Some(node) = nodes.next_mut() {
+            f(node);
+        }
+    }
+
+    /// visits this node and its descendants in pre-order and replaces the accumulator of each node
+    /// with f applied to the accumulator of its parent and its value, starting with init for this
+    /// node. The values may be changed on the way, like inherited attributes.
+    fn propagate_down<A>(&mut self, init: A, mut f: impl FnMut(&A, &mut Self::Type) -> A) {
+        let node = self.get_mut();
+        let buffer = node.buffer;
+        unsafe {
+            // the accumulators of the nodes on the current path
+            let mut accumulators = vec![f(&init, (*buffer).get_raw_mut(node.index).get_value_mut())];
+            // the nodes on the current path and the position of their next child
+            let mut stack = vec![(node.index, 0)];
+            while let Some((index, position)) = stack.last_mut() {
+                if let Some(child) = (*buffer).get_raw(*index).childs().get(*position) {
+                    *position += 1;
+                    let accumulator = f(accumulators.last().unwrap(), (*buffer).get_raw_mut(child.get()).get_value_mut());
+                    accumulators.push(accumulator);
+                    stack.push((child.get(), 0));
+                } else {
+                    accumulators.pop();
+                    stack.pop();
+                }
+            }
+        }
+    }
+}
+
+impl<'a, T: Debug + 'static> Debug for RefMut<'a, T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        DebugNode(self.get_ref()).fmt(f)
+    }
+}
@@ -1,10 +1,11 @@
 use std::ops::{Receiver, Deref, DerefMut};
 use crate::ref_mut::{RefMut, TreeRefMut};
 use crate::tree::{Tree, Element};
-use crate::reference::{TreeRef, Ref};
+use crate::reference::{TreeRef, Ref, DebugNode};
 use crate::iter::ChildIter;
 use crate::children_mut::ChildrenMut;
 use crate::child_unique::ChildUniq;
+use std::fmt::{Debug, Formatter};
 
 /// RefUniq is an unique Reference to node of the Tree.
 /// it has all capabilities of RefMut but additionally can change the structure of the Tree (adding
@@ -46,6 +47,11 @@ impl<'a, T: 'static> RefUniq<'a, T> {
         self.inner.add_child(value)
     }
 
+    /// appends all values as children, see [`ChildUniq::add_children`]
+    pub fn add_children(&mut self, values: impl IntoIterator<Item = T>) {
+        self.inner.add_children(values)
+    }
+
     pub fn remove_child(&mut self, index: u32) -> T {
         self.inner.remove_child(index)
     }
@@ -56,8 +62,9 @@ impl<'a, T: 'static> RefUniq<'a, T> {
 
     pub fn into_parent(self) -> Result<Self, Self> {
         unsafe {
-            if self.index() != 0 {
-                Ok(RefUniq::create(self.raw().parent(), self.buffer()))
+            let parent = self.raw().parent();
+            if self.index() != 0 && self.raw_index(parent).is_used() {
+                Ok(RefUniq::create(parent, self.buffer()))
             } else {
                 Err(self)
             }
@@ -142,4 +149,10 @@ impl<'a, T: 'static> TreeRef for RefUniq<'a, T> {
     fn get_ref<'b>(&'b self) -> Ref<'b, Self::Type> {
         self.inner.get_ref()
     }
-}
\ No newline at end of file
+}
+
+impl<'a, T: Debug + 'static> Debug for RefUniq<'a, T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        DebugNode(self.get_ref()).fmt(f)
+    }
+}
@@ -1,7 +1,8 @@
 use std::ops::{Receiver, Deref};
-use smallvec::alloc::fmt::{Display, Formatter};
+use smallvec::alloc::fmt::{Display, Debug, Formatter, Write};
 use crate::tree::{Tree, Element};
-use crate::iter::ChildIter;
+use crate::iter::{ChildIter, PreOrder, Edges, Edge};
+use crate::parse::write_escaped;
 
 pub struct Ref<'a, T> {
     buffer: &'a Tree<T>,
@@ -15,9 +16,25 @@ impl<'a, T: 'static> Ref<'a, T> {
             buffer,
         }
     }
+    /// iterates over the Open and Close events of a depth-first traversal of this node
+    pub fn edges(&self) -> Edges<'a, T> {
+        unsafe {
+            Edges::new(self.buffer, self.index)
+        }
+    }
+    /// returns the Tree of this node
+    pub(crate) fn tree(&self) -> &'a Tree<T> {
+        self.buffer
+    }
     unsafe fn raw(&self) -> &Element<T> {
         &self.buffer.get_raw(self.index)
     }
+    /// iterates over this node and all of its descendants in pre-order
+    pub fn pre_order(&self) -> PreOrder<'a, T> {
+        unsafe {
+            PreOrder::new(self.buffer, self.index)
+        }
+    }
 }
 
 impl<'a, T> Receiver for Ref<'a, T>{}
@@ -81,24 +98,152 @@ pub trait TreeRef {
     fn children_count(&self) -> u32;
 
     fn get_ref<'b>(&'b self) -> Ref<'b, Self::Type>;
+
+    /// folds the subtree of this node bottom-up. f is called for every node in post-order with its
+    /// value and the results of its children.
+    fn fold<R>(&self, mut f: impl FnMut(&Self::Type, &[R]) -> R) -> R {
+        let node = self.get_ref();
+        let buffer = node.buffer;
+        // the results of the children of the nodes on the current path
+        let mut results = Vec::new();
+        // the nodes on the current path and the position of their next child
+        let mut stack = vec![(node.index, 0)];
+        while let Some((index, position)) = stack.last_mut() {
+            let element = unsafe {buffer.get_raw(*index)};
+            if let Some(child) = element.childs().get(*position) {
+                *position += 1;
+                stack.push((child.get(), 0));
+            } else {
+                let start = results.len() - element.childs().len();
+                let result = f(unsafe {element.get_value()}, &results[start..]);
+                results.truncate(start);
+                results.push(result);
+                stack.pop();
+            }
+        }
+        results.pop().unwrap()
+    }
 }
 
 impl<'a, T: Display + 'static> Display for Ref<'a, T> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        self.deref().fmt(f)?;
-        let mut iter = self.children();
-        if let Some(value) = iter.next() {
-            f.write_str("(")?;
-            value.fmt(f)?;
-
-            for next in iter {
-                f.write_str(", ")?;
-                next.fmt(f)?;
+        // a node after a sibling is separated by a comma
+        let mut separate = false;
+        for edge in self.edges() {
+            match edge {
+                Edge::Open(node) => {
+                    if separate {
+                        f.write_str(", ")?;
+                    }
+                    write_escaped(f, node.deref())?;
+                    separate = node.children_count() == 0;
+                    if !separate {
+                        f.write_str("(")?;
+                    }
+                }
+                Edge::Close(node) => {
+                    if node.children_count() > 0 {
+                        f.write_str(")")?;
+                    }
+                    separate = true;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// formats a node as its value followed by the list of its children: `value [child, child [...]]`.
+/// The alternate form writes every child on its own line, indented by its depth.
+pub(crate) struct DebugNode<'a, T>(pub(crate) Ref<'a, T>);
+
+impl<'a, T: Debug + 'static> Debug for DebugNode<'a, T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let buffer = self.0.buffer;
+        let alternate = f.alternate();
+        write_debug_value(f, unsafe {buffer.get_raw(self.0.index).get_value()}, 0)?;
+        if unsafe {buffer.get_raw(self.0.index)}.childs().is_empty() {
+            return Ok(());
+        }
+        f.write_str(" [")?;
+
+        // the nodes with an open list and the position of their next child
+        let mut stack = vec![(self.0.index, 0)];
+        while !stack.is_empty() {
+            let depth = stack.len();
+            let (index, position) = &mut stack[depth - 1];
+            match unsafe {buffer.get_raw(*index)}.childs().get(*position) {
+                Some(child) => {
+                    if alternate {
+                        new_line(f, depth)?;
+                    } else if *position > 0 {
+                        f.write_str(", ")?;
+                    }
+                    *position += 1;
+                    let element = unsafe {buffer.get_raw(child.get())};
+                    write_debug_value(f, unsafe {element.get_value()}, depth)?;
+                    if !element.childs().is_empty() {
+                        f.write_str(" [")?;
+                        stack.push((child.get(), 0));
+                    } else if alternate {
+                        f.write_str(",")?;
+                    }
+                }
+                None => {
+                    stack.pop();
+                    if alternate {
+                        new_line(f, depth - 1)?;
+                    }
+                    f.write_str("]")?;
+                    if alternate && depth > 1 {
+                        f.write_str(",")?;
+                    }
+                }
             }
-            f.write_str(")")?;
         }
         Ok(())
     }
 }
 
-//TODO: impl Eq and Debug, Display, ToOwned for TreeRef
\ No newline at end of file
+/// writes value with Debug, in the alternate form every line of it is indented by depth
+fn write_debug_value<T: Debug>(f: &mut Formatter<'_>, value: &T, depth: usize) -> std::fmt::Result {
+    if f.alternate() && depth > 0 {
+        write!(Indent{ out: f, depth }, "{:#?}", value)
+    } else {
+        value.fmt(f)
+    }
+}
+
+fn new_line(f: &mut impl Write, depth: usize) -> std::fmt::Result {
+    f.write_char('\n')?;
+    for _ in 0..depth {
+        f.write_str("    ")?;
+    }
+    Ok(())
+}
+
+/// indents every line after the first of the text written through it by depth
+struct Indent<'a, W: Write> {
+    out: &'a mut W,
+    depth: usize,
+}
+
+impl<'a, W: Write> Write for Indent<'a, W> {
+    fn write_str(&mut self, s: &str) -> std::fmt::Result {
+        for (index, line) in s.split('\n').enumerate() {
+            if index > 0 {
+                new_line(self.out, self.depth)?;
+            }
+            self.out.write_str(line)?;
+        }
+        Ok(())
+    }
+}
+
+impl<'a, T: Debug + 'static> Debug for Ref<'a, T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        DebugNode(self.get_ref()).fmt(f)
+    }
+}
+
+//TODO: impl Eq and ToOwned for TreeRef
\ No newline at end of file
@@ -0,0 +1,174 @@
+use std::num::NonZeroU32;
+use serde::{Serialize, Serializer, Deserialize, Deserializer};
+use serde::ser::{SerializeStruct, SerializeSeq};
+use crate::tree::{Tree, Element, MAX_INDEX};
+use crate::reference::{Ref, TreeRef};
+
+/// The nested mode is the default, every node is serialized as a struct `{value, children}` with
+/// children being a sequence of nodes. The indices of the nodes are not preserved, use [`flat`]
+/// for that.
+///
+/// The nested mode recurses once per level of the Tree, because a serde Serializer only accepts
+/// complete values as elements of a sequence. Its depth is therefore limited by the stack and the
+/// format, serde_json for example rejects more than 128 levels by default. Deep Trees should be
+/// stored with [`flat`], which does not recurse.
+impl<T: Serialize + 'static> Serialize for Tree<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        Nested(self.top()).serialize(serializer)
+    }
+}
+
+impl<'de, T: Deserialize<'de> + 'static> Deserialize<'de> for Tree<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let OwnedNode{ value, children } = OwnedNode::deserialize(deserializer)?;
+        let mut tree = Tree::new(value);
+        // the nodes whose children are currently inserted
+        let mut stack = vec![(0, children.into_iter())];
+        while let Some((parent, children)) = stack.last_mut() {
+            let parent = *parent;
+            if let Some(OwnedNode{ value, children }) = children.next() {
+                let index = unsafe {tree.push_child(parent, value)};
+                stack.push((index.get(), children.into_iter()));
+            } else {
+                stack.pop();
+            }
+        }
+        Ok(tree)
+    }
+}
+
+struct Nested<'a, T>(Ref<'a, T>);
+
+impl<'a, T: Serialize + 'static> Serialize for Nested<'a, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut node = serializer.serialize_struct("Node", 2)?;
+        node.serialize_field("value", &*self.0)?;
+        node.serialize_field("children", &NestedChildren(&self.0))?;
+        node.end()
+    }
+}
+
+struct NestedChildren<'a, 'b, T>(&'b Ref<'a, T>);
+
+impl<'a, 'b, T: Serialize + 'static> Serialize for NestedChildren<'a, 'b, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.0.children().map(Nested))
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename = "Node")]
+struct OwnedNode<T> {
+    value: T,
+    #[serde(default = "Vec::new")]
+    children: Vec<OwnedNode<T>>,
+}
+
+/// The flat mode serializes the pool of the Tree as a sequence with one slot per Element. A used
+/// Element is a struct `{value, parent, position}`, where parent is the index of the parent (none
+/// for the root) and position the position among the children of the parent. An unused Element
+/// is serialized as none.
+///
+/// Since the indices of the nodes are preserved, indices stored outside of the Tree stay valid
+/// across a save/load cycle. The queue of unused Elements is rebuilt in ascending order.
+///
+/// Use it with `#[serde(with = "pool_tree::serialize::flat")]`.
+pub mod flat {
+    use super::*;
+
+    #[derive(Serialize)]
+    #[serde(rename = "Slot")]
+    struct SlotRef<'a, T> {
+        value: &'a T,
+        parent: Option<u32>,
+        position: u32,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(rename = "Slot")]
+    struct Slot<T> {
+        value: T,
+        parent: Option<u32>,
+        position: u32,
+    }
+
+    pub fn serialize<T: Serialize + 'static, S: Serializer>(tree: &Tree<T>, serializer: S) -> Result<S::Ok, S::Error> {
+        let length = tree.pool_len();
+        let mut positions = vec![0; length];
+        for index in 0..length as u32 {
+            for (position, child) in unsafe {tree.get_raw(index)}.childs().iter().enumerate() {
+                positions[child.get() as usize] = position as u32;
+            }
+        }
+
+        let mut slots = serializer.serialize_seq(Some(length))?;
+        for index in 0..length as u32 {
+            let element = unsafe {tree.get_raw(index)};
+            let slot = element.get_value_checked().map(|value|SlotRef {
+                value,
+                parent: if index == 0 { None } else { Some(element.parent()) },
+                position: positions[index as usize],
+            });
+            slots.serialize_element(&slot)?;
+        }
+        slots.end()
+    }
+
+    pub fn deserialize<'de, T: Deserialize<'de> + 'static, D: Deserializer<'de>>(deserializer: D) -> Result<Tree<T>, D::Error> {
+        let slots: Vec<Option<Slot<T>>> = Deserialize::deserialize(deserializer)?;
+        from_slots(slots).map_err(serde::de::Error::custom)
+    }
+
+    fn from_slots<T: 'static>(slots: Vec<Option<Slot<T>>>) -> Result<Tree<T>, &'static str> {
+        if slots.len() > MAX_INDEX as usize + 1 {
+            return Err("the pool exceeds the maximum number of nodes");
+        }
+        match slots.first() {
+            Some(Some(Slot{ parent: None, .. })) => {}
+            Some(Some(_)) => return Err("the root must not have a parent"),
+            _ => return Err("the root is missing"),
+        }
+
+        // the positions and indices of the children of every node
+        let mut children: Vec<Vec<(u32, NonZeroU32)>> = slots.iter().map(|_|Vec::new()).collect();
+        for (index, slot) in slots.iter().enumerate().skip(1) {
+            if let Some(slot) = slot {
+                let parent = slot.parent.ok_or("only the root may have no parent")?;
+                if !slots.get(parent as usize).is_some_and(|parent|parent.is_some()) {
+                    return Err("the parent of a node is unused");
+                }
+                children[parent as usize].push((slot.position, NonZeroU32::new(index as u32).unwrap()));
+            }
+        }
+        for childs in &mut children {
+            childs.sort_unstable_by_key(|(position, _)|*position);
+            if childs.iter().enumerate().any(|(expected, (position, _))|*position as usize != expected) {
+                return Err("the positions of the children of a node are not consecutive");
+            }
+        }
+
+        let free: Vec<NonZeroU32> = (1..slots.len() as u32).filter(|index|slots[*index as usize].is_none()).map(|index|NonZeroU32::new(index).unwrap()).collect();
+        let mut free = free.into_iter();
+        let next_free = free.next();
+        let mut used = 0;
+        let mut buffer = Vec::with_capacity(slots.len());
+        for (slot, childs) in slots.into_iter().zip(children) {
+            buffer.push(match slot {
+                Some(Slot{ value, parent, .. }) => {
+                    used += 1;
+                    let mut element = Element::new(value, parent.unwrap_or(0));
+                    element.childs.extend(childs.into_iter().map(|(_, index)|index));
+                    element
+                }
+                None => unsafe {Element::unused(free.next())},
+            });
+        }
+        let tree = unsafe {Tree::from_raw_parts(buffer, next_free)};
+
+        // nodes on a cycle of parents are not reachable from the root
+        if tree.top().pre_order().count() != used {
+            return Err("the parents contain a cycle");
+        }
+        Ok(tree)
+    }
+}
@@ -0,0 +1,355 @@
+use std::fmt::{Display, Debug, Formatter, Write};
+use std::str::FromStr;
+use std::error::Error;
+use crate::tree::Tree;
+
+/// An atom of an S-expression
+#[derive(Clone, Debug, PartialEq)]
+pub enum Atom {
+    Symbol(String),
+    String(String),
+    Integer(i64),
+    Float(f64),
+}
+
+impl Display for Atom {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Atom::Symbol(symbol) => write_symbol(f, symbol),
+            Atom::String(string) => write_string(f, string),
+            Atom::Integer(integer) => write!(f, "{}", integer),
+            Atom::Float(float) if float.is_nan() => f.write_str("+nan.0"),
+            Atom::Float(float) if float.is_infinite() => f.write_str(if *float > 0.0 { "+inf.0" } else { "-inf.0" }),
+            Atom::Float(float) if float.fract() == 0.0 => write!(f, "{:.1}", float),
+            Atom::Float(float) => write!(f, "{}", float),
+        }
+    }
+}
+
+/// A type which can be read from and written to an atom of an S-expression. It is implemented for
+/// Atom and for every type implementing FromStr and Display. Those read symbols and the content of
+/// strings with FromStr and write values as strings, if they would not be read as one symbol.
+pub trait SexprAtom: Sized {
+    type Err;
+
+    fn from_symbol(symbol: &str) -> Result<Self, Self::Err>;
+
+    fn from_string(string: String) -> Result<Self, Self::Err>;
+
+    /// reads a symbol written in vertical bars, like an unquoted symbol by default
+    fn from_quoted_symbol(symbol: String) -> Result<Self, Self::Err> {
+        Self::from_symbol(&symbol)
+    }
+
+    fn write_atom(&self, out: &mut dyn Write) -> std::fmt::Result;
+}
+
+impl SexprAtom for Atom {
+    type Err = std::convert::Infallible;
+
+    fn from_symbol(symbol: &str) -> Result<Self, Self::Err> {
+        // only symbols starting like a number are numbers, so inf or nan stay symbols
+        let numeric = symbol.trim_start_matches(&['+', '-'][..]).starts_with(|c: char|c.is_ascii_digit() || c == '.');
+        Ok(if let Some(float) = non_finite(symbol) {
+            Atom::Float(float)
+        } else if let (true, Ok(integer)) = (numeric, symbol.parse()) {
+            Atom::Integer(integer)
+        } else if let (true, Ok(float)) = (numeric, symbol.parse()) {
+            Atom::Float(float)
+        } else {
+            Atom::Symbol(symbol.to_string())
+        })
+    }
+
+    fn from_string(string: String) -> Result<Self, Self::Err> {
+        Ok(Atom::String(string))
+    }
+
+    fn from_quoted_symbol(symbol: String) -> Result<Self, Self::Err> {
+        Ok(Atom::Symbol(symbol))
+    }
+
+    fn write_atom(&self, out: &mut dyn Write) -> std::fmt::Result {
+        write!(out, "{}", self)
+    }
+}
+
+impl<T: FromStr + Display> SexprAtom for T {
+    type Err = T::Err;
+
+    fn from_symbol(symbol: &str) -> Result<Self, Self::Err> {
+        symbol.parse()
+    }
+
+    fn from_string(string: String) -> Result<Self, Self::Err> {
+        string.parse()
+    }
+
+    fn write_atom(&self, out: &mut dyn Write) -> std::fmt::Result {
+        let text = self.to_string();
+        if text.is_empty() || text.chars().any(|c|c.is_whitespace() || matches!(c, '(' | ')' | '"' | ';' | '|')) {
+            write_string(out, &text)
+        } else {
+            out.write_str(&text)
+        }
+    }
+}
+
+/// The error returned by [`Tree::from_sexpr`]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SexprError<E> {
+    /// the byte offset in the input at which the error occurred
+    pub position: usize,
+    pub kind: SexprErrorKind<E>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum SexprErrorKind<E> {
+    UnexpectedEnd,
+    UnexpectedChar(char),
+    /// a list is empty or starts with another list, therefore it has no value for its node
+    MissingHead,
+    InvalidEscape,
+    InvalidAtom(E),
+}
+
+impl<E: Display> Display for SexprError<E> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match &self.kind {
+            SexprErrorKind::UnexpectedEnd => write!(f, "unexpected end of input at {}", self.position),
+            SexprErrorKind::UnexpectedChar(c) => write!(f, "unexpected character {:?} at {}", c, self.position),
+            SexprErrorKind::MissingHead => write!(f, "list without an atom as head at {}", self.position),
+            SexprErrorKind::InvalidEscape => write!(f, "invalid escape sequence at {}", self.position),
+            SexprErrorKind::InvalidAtom(error) => write!(f, "invalid atom at {}: {}", self.position, error),
+        }
+    }
+}
+
+impl<E: Debug + Display> Error for SexprError<E> {}
+
+impl<T: SexprAtom + 'static> Tree<T> {
+    /// parses a single S-expression. A list `(head child child)` becomes a node with the value of
+    /// its head and the remaining elements as children, an atom becomes a leaf.
+    ///
+    /// Atoms are symbols, which end at whitespace, parentheses, `"` or `;`, or strings in double
+    /// quotes, which support the escapes `\"`, `\\`, `\n` and `\t`. Symbols can also be written in
+    /// vertical bars like `|a b|`, which support the escapes `\|` and `\\`. Comments start with `;`
+    /// and reach to the end of the line.
+    pub fn from_sexpr(text: &str) -> Result<Self, SexprError<T::Err>> {
+        let mut parser = Parser{ text, position: 0 };
+        let mut tree: Option<Tree<T>> = None;
+        // the nodes of the currently open lists
+        let mut open: Vec<u32> = Vec::new();
+
+        loop {
+            parser.skip();
+            let position = parser.position;
+            let (list, value) = match parser.peek() {
+                None if open.is_empty() && tree.is_none() => return Err(parser.error(SexprErrorKind::UnexpectedEnd)),
+                None if open.is_empty() => return Ok(tree.unwrap()),
+                None => return Err(parser.error(SexprErrorKind::UnexpectedEnd)),
+                Some(c) if open.is_empty() && tree.is_some() => return Err(parser.error(SexprErrorKind::UnexpectedChar(c))),
+                Some(')') if !open.is_empty() => {
+                    parser.position += 1;
+                    open.pop();
+                    continue;
+                }
+                Some('(') => {
+                    parser.position += 1;
+                    parser.skip();
+                    if matches!(parser.peek(), Some('(') | Some(')')) {
+                        return Err(SexprError{ position, kind: SexprErrorKind::MissingHead });
+                    }
+                    (true, parser.atom()?)
+                }
+                Some(_) => (false, parser.atom()?),
+            };
+
+            let index = match (&mut tree, open.last()) {
+                (Some(tree), Some(parent)) => unsafe {tree.push_child(*parent, value)}.get(),
+                (_, _) => {
+                    tree = Some(Tree::new(value));
+                    0
+                }
+            };
+            if list {
+                open.push(index);
+            }
+        }
+    }
+
+    /// writes the Tree as S-expression, nodes with children are written as list
+    pub fn to_sexpr(&self) -> String {
+        let mut out = String::new();
+        // the nodes and the position of the next child to write
+        let mut stack: Vec<(u32, usize)> = vec![(0, 0)];
+        unsafe {
+            if self.get_raw(0).childs().is_empty() {
+                self.get_raw(0).get_value().write_atom(&mut out).unwrap();
+                return out;
+            }
+            out.push('(');
+            self.get_raw(0).get_value().write_atom(&mut out).unwrap();
+
+            while let Some((index, position)) = stack.last_mut() {
+                if let Some(child) = self.get_raw(*index).childs().get(*position) {
+                    *position += 1;
+                    out.push(' ');
+                    let element = self.get_raw(child.get());
+                    if element.childs().is_empty() {
+                        element.get_value().write_atom(&mut out).unwrap();
+                    } else {
+                        out.push('(');
+                        element.get_value().write_atom(&mut out).unwrap();
+                        stack.push((child.get(), 0));
+                    }
+                } else {
+                    out.push(')');
+                    stack.pop();
+                }
+            }
+        }
+        out
+    }
+}
+
+/// returns the float written as `+inf.0`, `-inf.0` or `+nan.0`
+fn non_finite(symbol: &str) -> Option<f64> {
+    match symbol {
+        "+inf.0" => Some(f64::INFINITY),
+        "-inf.0" => Some(f64::NEG_INFINITY),
+        "+nan.0" | "-nan.0" => Some(f64::NAN),
+        _ => None,
+    }
+}
+
+/// writes a symbol in vertical bars, if it would not be read back as the same symbol otherwise
+fn write_symbol(out: &mut dyn Write, symbol: &str) -> std::fmt::Result {
+    let plain = !symbol.is_empty()
+        && !symbol.chars().any(|c|c.is_whitespace() || matches!(c, '(' | ')' | '"' | ';' | '|'))
+        && matches!(Atom::from_symbol(symbol), Ok(Atom::Symbol(_)));
+    if plain {
+        return out.write_str(symbol);
+    }
+    out.write_char('|')?;
+    for c in symbol.chars() {
+        match c {
+            '|' => out.write_str("\\|")?,
+            '\\' => out.write_str("\\\\")?,
+            c => out.write_char(c)?,
+        }
+    }
+    out.write_char('|')
+}
+
+fn write_string(out: &mut dyn Write, value: &str) -> std::fmt::Result {
+    out.write_char('"')?;
+    for c in value.chars() {
+        match c {
+            '"' => out.write_str("\\\"")?,
+            '\\' => out.write_str("\\\\")?,
+            '\n' => out.write_str("\\n")?,
+            '\t' => out.write_str("\\t")?,
+            c => out.write_char(c)?,
+        }
+    }
+    out.write_char('"')
+}
+
+struct Parser<'a> {
+    text: &'a str,
+    position: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<char> {
+        self.text[self.position..].chars().next()
+    }
+
+    fn error<E>(&self, kind: SexprErrorKind<E>) -> SexprError<E> {
+        SexprError{ position: self.position, kind }
+    }
+
+    /// skips whitespace and comments
+    fn skip(&mut self) {
+        loop {
+            match self.peek() {
+                Some(c) if c.is_whitespace() => self.position += c.len_utf8(),
+                Some(';') => {
+                    let rest = &self.text[self.position..];
+                    self.position += rest.find('\n').unwrap_or(rest.len());
+                }
+                _ => return,
+            }
+        }
+    }
+
+    fn atom<T: SexprAtom>(&mut self) -> Result<T, SexprError<T::Err>> {
+        let start = self.position;
+        let invalid = |error|SexprError{ position: start, kind: SexprErrorKind::InvalidAtom(error) };
+        if self.peek() == Some('"') {
+            self.position += 1;
+            let mut string = String::new();
+            loop {
+                match self.peek() {
+                    Some('"') => {
+                        self.position += 1;
+                        return T::from_string(string).map_err(invalid);
+                    }
+                    Some('\\') => {
+                        self.position += 1;
+                        string.push(match self.peek() {
+                            Some('"') => '"',
+                            Some('\\') => '\\',
+                            Some('n') => '\n',
+                            Some('t') => '\t',
+                            Some(_) => return Err(self.error(SexprErrorKind::InvalidEscape)),
+                            None => return Err(self.error(SexprErrorKind::UnexpectedEnd)),
+                        });
+                        self.position += 1;
+                    }
+                    Some(c) => {
+                        string.push(c);
+                        self.position += c.len_utf8();
+                    }
+                    None => return Err(self.error(SexprErrorKind::UnexpectedEnd)),
+                }
+            }
+        } else if self.peek() == Some('|') {
+            self.position += 1;
+            let mut symbol = String::new();
+            loop {
+                match self.peek() {
+                    Some('|') => {
+                        self.position += 1;
+                        return T::from_quoted_symbol(symbol).map_err(invalid);
+                    }
+                    Some('\\') => {
+                        self.position += 1;
+                        symbol.push(match self.peek() {
+                            Some('|') => '|',
+                            Some('\\') => '\\',
+                            Some(_) => return Err(self.error(SexprErrorKind::InvalidEscape)),
+                            None => return Err(self.error(SexprErrorKind::UnexpectedEnd)),
+                        });
+                        self.position += 1;
+                    }
+                    Some(c) => {
+                        symbol.push(c);
+                        self.position += c.len_utf8();
+                    }
+                    None => return Err(self.error(SexprErrorKind::UnexpectedEnd)),
+                }
+            }
+        } else {
+            let rest = &self.text[self.position..];
+            let end = rest.find(|c: char|c.is_whitespace() || matches!(c, '(' | ')' | '"' | ';')).unwrap_or(rest.len());
+            if end == 0 {
+                let c = self.peek().unwrap();
+                return Err(self.error(SexprErrorKind::UnexpectedChar(c)));
+            }
+            self.position += end;
+            T::from_symbol(&rest[..end]).map_err(invalid)
+        }
+    }
+}
@@ -0,0 +1,124 @@
+use std::fmt::{Display, Formatter};
+use std::error::Error;
+use crate::tree::Tree;
+use crate::reference::TreeRef;
+
+/// The error returned when a parent array or an edge list does not describe a single tree
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum StructureError {
+    /// the number of values differs from the number of parents
+    LengthMismatch { values: usize, parents: usize },
+    /// no node is without a parent
+    NoRoot,
+    /// both nodes have no parent
+    MultipleRoots(usize, usize),
+    /// the parent of node is outside of the values
+    DanglingParent { node: usize, parent: usize },
+    /// an edge refers to a child outside of the values
+    NodeOutOfRange(usize),
+    /// the node has more than one incoming edge
+    MultipleParents(usize),
+    /// the node is part of a cycle or a descendant of one and therefore not reachable from the root
+    Cycle(usize),
+}
+
+impl Display for StructureError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StructureError::LengthMismatch{ values, parents } => write!(f, "{} values but {} parents", values, parents),
+            StructureError::NoRoot => f.write_str("no node without parent"),
+            StructureError::MultipleRoots(first, second) => write!(f, "the nodes {} and {} have no parent", first, second),
+            StructureError::DanglingParent{ node, parent } => write!(f, "the parent {} of node {} does not exist", parent, node),
+            StructureError::NodeOutOfRange(node) => write!(f, "the node {} does not exist", node),
+            StructureError::MultipleParents(node) => write!(f, "the node {} has multiple parents", node),
+            StructureError::Cycle(node) => write!(f, "the node {} is not reachable from the root", node),
+        }
+    }
+}
+
+impl Error for StructureError {}
+
+impl<T: 'static> Tree<T> {
+    /// builds a Tree from values and the index of the parent of each value, the root has none.
+    /// The children of a node keep the order of their indices. The nodes are allocated in
+    /// pre-order, so the result of [`Tree::to_parent_array`] is restored with the same numbering.
+    pub fn from_parent_array(values: Vec<T>, parents: &[Option<usize>]) -> Result<Self, StructureError> {
+        if values.len() != parents.len() {
+            return Err(StructureError::LengthMismatch{ values: values.len(), parents: parents.len() });
+        }
+        let mut root = None;
+        let mut children = vec![Vec::new(); values.len()];
+        for (node, parent) in parents.iter().enumerate() {
+            match (*parent, root) {
+                (None, None) => root = Some(node),
+                (None, Some(first)) => return Err(StructureError::MultipleRoots(first, node)),
+                (Some(parent), _) if parent >= values.len() => return Err(StructureError::DanglingParent{ node, parent }),
+                (Some(parent), _) => children[parent].push(node),
+            }
+        }
+        let root = root.ok_or(StructureError::NoRoot)?;
+
+        let count = values.len();
+        let mut values: Vec<Option<T>> = values.into_iter().map(Some).collect();
+        let mut tree = Tree::with_capacity(values[root].take().unwrap(), count);
+        // the nodes whose children are currently inserted
+        let mut stack = vec![(0, children[root].iter())];
+        while let Some((parent, nodes)) = stack.last_mut() {
+            let parent = *parent;
+            if let Some(&node) = nodes.next() {
+                let index = unsafe {tree.push_child(parent, values[node].take().unwrap())};
+                stack.push((index.get(), children[node].iter()));
+            } else {
+                stack.pop();
+            }
+        }
+
+        match values.iter().position(|value|value.is_some()) {
+            Some(node) => Err(StructureError::Cycle(node)),
+            None => Ok(tree),
+        }
+    }
+
+    /// builds a Tree from values and edges given as (parent, child) pairs of indices into values.
+    /// The children of a node keep the order of their indices.
+    pub fn from_edges(values: Vec<T>, edges: &[(usize, usize)]) -> Result<Self, StructureError> {
+        let mut parents = vec![None; values.len()];
+        for &(parent, child) in edges {
+            let slot = parents.get_mut(child).ok_or(StructureError::NodeOutOfRange(child))?;
+            if parent >= values.len() {
+                return Err(StructureError::DanglingParent{ node: child, parent });
+            }
+            if slot.replace(parent).is_some() {
+                return Err(StructureError::MultipleParents(child));
+            }
+        }
+        Tree::from_parent_array(values, &parents)
+    }
+
+    /// returns the values in pre-order and the position of the parent of each value in this order
+    pub fn to_parent_array(&self) -> (Vec<&T>, Vec<Option<usize>>) {
+        let mut values = Vec::new();
+        let mut parents = Vec::new();
+        // the position of every visited node by its index in the pool
+        let mut positions = vec![0; self.pool_len()];
+        for node in self.top().pre_order() {
+            let index = node.index();
+            positions[index as usize] = values.len();
+            parents.push(if index == 0 {
+                None
+            } else {
+                Some(positions[unsafe {self.get_raw(index)}.parent() as usize])
+            });
+            values.push(unsafe {self.get_raw(index).get_value()});
+        }
+        (values, parents)
+    }
+
+    /// returns the values in pre-order and the edges as (parent, child) pairs of positions in this
+    /// order. The edges are sorted by their child.
+    pub fn to_edge_list(&self) -> (Vec<&T>, Vec<(usize, usize)>) {
+        let (values, parents) = self.to_parent_array();
+        let edges = parents.into_iter().enumerate().filter_map(|(child, parent)|parent.map(|parent|(parent, child))).collect();
+        (values, edges)
+    }
+}
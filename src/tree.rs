@@ -1,37 +1,45 @@
 use std::num::NonZeroU32;
-use std::mem::replace;
+use std::mem::{replace, take};
 use std::fmt::{Debug, Formatter};
 use smallvec::SmallVec;
-use std::hint::unreachable_unchecked;
+use std::mem::MaybeUninit;
 use smallvec::alloc::fmt::Display;
-use crate::reference::Ref;
+use crate::reference::{Ref, TreeRef};
 use crate::ref_unique::RefUniq;
 
 /// Element stores the value of a Node as well as the indices of its parent and its children.
-/// The value field uses an Option<T> to avoid an extra field used. parent_next_is the index of the
-/// parent if value is Some and the next unused value if the value is None.
+/// The value is stored as MaybeUninit<T> to avoid the discriminant of an Option<T>. Instead the
+/// highest bit of parent_next_free marks the Element as used. The remaining bits are the index of
+/// the parent if the Element is used and the next unused Element (0 meaning None) if it is unused.
 pub struct Element<T> {
-    pub value: Option<T>,
-    pub parent_next_free: Option<NonZeroU32>,
+    value: MaybeUninit<T>,
+    parent_next_free: u32,
     pub childs: SmallVec<[NonZeroU32; 5]>,
 
 }
 
+/// the bit of parent_next_free which marks an Element as used
+const USED_FLAG: u32 = 1 << 31;
+
+/// the largest index an Element can have, since the highest bit is used as the USED_FLAG
+pub const MAX_INDEX: u32 = USED_FLAG - 1;
+
 //TODO: decide whether the methods should panic or trigger undefined behaviour
 impl<T> Element<T> {
     /// Creates a new used Element with no Children and the given value and parent
     pub fn new(value: T, parent: u32) -> Self {
+        debug_assert!(parent <= MAX_INDEX);
         Element {
-            value: Some(value),
-            parent_next_free: NonZeroU32::new(parent),
+            value: MaybeUninit::new(value),
+            parent_next_free: parent | USED_FLAG,
             childs: SmallVec::new(),
         }
     }
     /// Creates a new unused Element with the given next_free index, value is None
     pub unsafe fn unused(next: Option<NonZeroU32>) -> Self {
         Element{
-            value: None,
-            parent_next_free: next,
+            value: MaybeUninit::uninit(),
+            parent_next_free: next.map_or(0, |n|n.get()),
             childs: SmallVec::new(),
         }
     }
@@ -44,7 +52,8 @@ impl<T> Element<T> {
     /// this method since the Ref itself only gets constructed with indices to valid used Elements
     #[inline]
     pub unsafe fn get_value(&self) -> &T {
-        self.value.as_ref().unwrap_or_else(||unreachable_unchecked())
+        debug_assert!(self.is_used());
+        &*self.value.as_ptr()
     }
 
     /// returns a reference to the value.
@@ -55,34 +64,50 @@ impl<T> Element<T> {
     /// this method since the Ref itself only gets constructed with indices to valid used Elements
     #[inline]
     pub unsafe fn get_value_mut(&mut self) -> &mut T {
-        self.value.as_mut().unwrap_or_else(||unreachable_unchecked())
+        debug_assert!(self.is_used());
+        &mut *self.value.as_mut_ptr()
     }
 
     /// returns a reference to the value if it is present
     #[inline]
     pub fn get_value_checked(&self) -> Option<&T> {
-        self.value.as_ref()
+        if self.is_used() {
+            Some(unsafe {self.get_value()})
+        } else {
+            None
+        }
     }
 
     /// returns a mutable reference to the value if it is present
     #[inline]
     pub fn get_value_checked_mut(&mut self) -> Option<&mut T> {
-        self.value.as_mut()
+        if self.is_used() {
+            Some(unsafe {self.get_value_mut()})
+        } else {
+            None
+        }
     }
 
     /// returns true if the value is present and therefore part of the Tree
     #[inline]
     pub fn is_used(&self) -> bool {
-        self.value.is_some()
+        self.parent_next_free & USED_FLAG != 0
     }
 
     /// equivalent to *self = Element::new(value, parent)
     ///
     /// assumes, that the value was previosly unused and returns the next unused value
+    ///
+    /// #Panics
+    /// Panics if the Element was already used
     #[inline]
     pub fn set_used(&mut self, value: T, parent: u32) -> Option<NonZeroU32> {
-        self.value.replace(value);
-        replace(&mut self.parent_next_free, NonZeroU32::new(parent))
+        if self.is_used() {
+            panic!("used an used Element!");
+        }
+        debug_assert!(parent <= MAX_INDEX);
+        self.value = MaybeUninit::new(value);
+        NonZeroU32::new(replace(&mut self.parent_next_free, parent | USED_FLAG))
     }
 
     /// equivalent to ```*self = Element::unused(next_free)``` and returns the value of the Element
@@ -91,8 +116,11 @@ impl<T> Element<T> {
     /// Panics if the Element was already unused
     #[inline]
     pub fn set_unused(&mut self, next_free: Option<NonZeroU32>) -> T {
-        self.parent_next_free = next_free;
-        self.value.take().unwrap_or_else(||panic!("freed an unused Element!"))
+        if !self.is_used() {
+            panic!("freed an unused Element!");
+        }
+        self.parent_next_free = next_free.map_or(0, |n|n.get());
+        unsafe { self.value.as_ptr().read() }
     }
 
     /// changed the parent of the Element
@@ -102,7 +130,8 @@ impl<T> Element<T> {
     #[inline]
     pub fn set_parent(&mut self, index: u32) {
         if self.is_used() {
-            self.parent_next_free = NonZeroU32::new(index);
+            debug_assert!(index <= MAX_INDEX);
+            self.parent_next_free = index | USED_FLAG;
         } else {
             panic!("Changed Parent of unused Element!");
         }
@@ -112,7 +141,7 @@ impl<T> Element<T> {
     /// if the Element is unused this method returns an arbitrary number!
     #[inline]
     pub fn parent(&self) -> u32 {
-        self.parent_next_free.map_or(0, |n|n.get())
+        self.parent_next_free & !USED_FLAG
     }
 
     /// Sets the next free value
@@ -123,7 +152,7 @@ impl<T> Element<T> {
     pub fn set_next_free(&mut self, next: Option<NonZeroU32>) {
 
         if !self.is_used() {
-            self.parent_next_free = next;
+            self.parent_next_free = next.map_or(0, |n|n.get());
         } else {
             panic!("set next free of used Element!");
         }
@@ -133,7 +162,7 @@ impl<T> Element<T> {
     /// if the Element is unused this method returns an arbitrary number!
     #[inline]
     pub fn next_free(&self) -> Option<NonZeroU32> {
-        self.parent_next_free.clone()
+        NonZeroU32::new(self.parent_next_free & !USED_FLAG)
     }
 
     ///Returns the indices of all children of this Node
@@ -142,6 +171,38 @@ impl<T> Element<T> {
         &*self.childs
     }
 
+    /// creates an Element with the same parent or next unused Element and the same childs, which
+    /// holds value
+    ///
+    /// #Panics
+    /// Panics if value is None for a used Element or Some for an unused one
+    pub(crate) fn with_layout<U>(&self, value: Option<U>) -> Element<U> {
+        assert_eq!(self.is_used(), value.is_some(), "value does not match the layout!");
+        Element {
+            value: value.map_or(MaybeUninit::uninit(), MaybeUninit::new),
+            parent_next_free: self.parent_next_free,
+            childs: self.childs.clone(),
+        }
+    }
+
+    /// moves the value and childs of this Element into a new Element with the same layout, the
+    /// value is converted with map. This Element is left unused without childs.
+    pub(crate) fn take_with_layout<U>(&mut self, map: impl FnOnce(T) -> U) -> Element<U> {
+        let parent_next_free = self.parent_next_free;
+        let childs = std::mem::take(&mut self.childs);
+        let value = if self.is_used() {
+            self.parent_next_free = 0;
+            MaybeUninit::new(map(unsafe {self.value.as_ptr().read()}))
+        } else {
+            MaybeUninit::uninit()
+        };
+        Element {
+            value,
+            parent_next_free,
+            childs,
+        }
+    }
+
     ///
     //TODO: dont leak internal details: impl SomeCollectionTrait<NonZeroU32>
     #[inline]
@@ -150,23 +211,40 @@ impl<T> Element<T> {
     }
 }
 
+impl<T> Drop for Element<T> {
+    fn drop(&mut self) {
+        if self.is_used() {
+            unsafe { self.value.as_mut_ptr().drop_in_place() }
+        }
+    }
+}
+
 impl<T: Debug> Debug for Element<T> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         if self.is_used() {
             f.debug_struct("Element")
-                .field("value", &self.value)
+                .field("value", &self.get_value_checked())
                 .field("parent", &self.parent())
                 .field("childs", &self.childs())
                 .finish()
         } else {
             f.debug_struct("Element (unused)")
-                .field("value", &self.value)
                 .field("next_free", &self.next_free())
                 .finish()
         }
     }
 }
 
+/// The strategies of [`Tree::reroot`]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum RerootMode {
+    /// treats the Tree as undirected graph and reverses the parent edges along the path from the
+    /// new root to the old root, like evert in link-cut trees. All nodes are kept.
+    Undirected,
+    /// keeps only the subtree of the new root and frees all other nodes
+    Subtree,
+}
+
 /// Tree is a never empty tree, with its nodes stored in a Vec which serves as a pool allocator.
 /// The root node is always at index 0 which allows for some optimisations:
 ///  - the indices of child nodes are represented by NonZeroU32
@@ -189,6 +267,71 @@ impl<T: 'static> Tree<T> {
         }
     }
 
+    ///creates a new Tree with the given root Node and space for capacity Nodes
+    #[inline]
+    pub fn with_capacity(root: T, capacity: usize) -> Self {
+        let mut buffer = Vec::with_capacity(capacity.max(1));
+        buffer.push(Element::new(root, 0));
+        Tree {
+            buffer,
+            next_free: None,
+        }
+    }
+
+    /// creates a Tree whose Element at index 0 is unused. The Element only serves as the parent of
+    /// the nodes stored in its childs, this allows multiple roots or no root at all.
+    ///
+    /// Methods assuming a used root like top or mut_top must not be called on such a Tree.
+    pub(crate) fn without_root() -> Self {
+        Tree {
+            buffer: vec![unsafe {Element::unused(None)}],
+            next_free: None,
+        }
+    }
+
+    /// creates a Tree from a buffer of Elements and the head of the queue of unused Elements
+    ///
+    /// #Safety
+    /// the Element at index 0 must be used and the parents, childs and the queue of unused Elements
+    /// must be consistent
+    pub(crate) unsafe fn from_raw_parts(buffer: Vec<Element<T>>, next_free: Option<NonZeroU32>) -> Self {
+        Tree {
+            buffer,
+            next_free,
+        }
+    }
+
+    /// returns the number of Elements in the buffer including the unused ones
+    #[inline]
+    pub(crate) fn pool_len(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// drops all nodes except the Element at index 0, whose childs are cleared. The allocated
+    /// buffer is kept.
+    pub(crate) fn clear_descendants(&mut self) {
+        self.buffer.truncate(1);
+        self.buffer[0].childs.clear();
+        self.next_free = None;
+    }
+
+    /// returns the index of the first unused Element in the queue of free Elements
+    #[inline]
+    pub(crate) fn first_free(&self) -> Option<NonZeroU32> {
+        self.next_free
+    }
+
+    /// returns the number of Elements the buffer can hold without reallocating
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.buffer.capacity()
+    }
+
+    /// reserves space for at least additional more nodes in the buffer
+    pub fn reserve(&mut self, additional: usize) {
+        self.buffer.reserve(additional)
+    }
+
     /// allocates an Element for a Node, with the given value and parent
     ///
     /// #Safety
@@ -201,6 +344,7 @@ impl<T: 'static> Tree<T> {
             self.next_free = element.set_used(value, parent);
             index
         } else {
+            assert!(self.buffer.len() <= MAX_INDEX as usize, "Tree exceeded the maximum number of nodes!");
             self.buffer.push(Element::new(value, parent));
 
             //Buffer is never empty, therefore is the new last Index greater than 0
@@ -220,6 +364,191 @@ impl<T: 'static> Tree<T> {
         element.set_unused(previous_free)
     }
 
+    /// frees all descendants of the node at index without recursion and clears its childs, the
+    /// node itself stays allocated. The descendants are freed in post-order.
+    ///
+    /// #Safety
+    /// index must be a used Element
+    pub(crate) unsafe fn free_descendants(&mut self, index: u32) {
+        // the nodes on the current path, None for index, and their children which are not freed yet
+        let mut stack = vec![(None, take(&mut self.get_raw_mut(index).childs).into_iter())];
+        while let Some((_, childs)) = stack.last_mut() {
+            match childs.next() {
+                Some(child) => {
+                    let childs = take(&mut self.get_raw_mut(child.get()).childs).into_iter();
+                    stack.push((Some(child), childs));
+                }
+                None => {
+                    if let Some((Some(node), _)) = stack.pop() {
+                        self.free(node);
+                    }
+                }
+            }
+        }
+    }
+
+    /// allocates a new Node with the given value and appends it to the childs of parent
+    ///
+    /// #Safety
+    /// parent must be inside the buffers bounds
+    pub(crate) unsafe fn push_child(&mut self, parent: u32, value: T) -> NonZeroU32 {
+        let index = self.alloc_for(value, parent);
+        self.get_raw_mut(parent).childs.push(index);
+        index
+    }
+
+    /// removes the node at index from the childs of its parent. The node stays allocated.
+    ///
+    /// #Safety
+    /// index must be a used Element
+    pub(crate) unsafe fn detach(&mut self, index: NonZeroU32) {
+        let parent = self.get_raw(index.get()).parent();
+        let childs = &mut self.get_raw_mut(parent).childs;
+        let position = childs.iter().position(|child|*child == index).expect("node is not a child of its parent!");
+        childs.remove(position);
+    }
+
+    /// returns true if the node at ancestor is the node at index or one of its ancestors
+    ///
+    /// #Safety
+    /// index must be a used Element
+    pub(crate) unsafe fn is_ancestor(&self, ancestor: u32, mut index: u32) -> bool {
+        loop {
+            if index == ancestor {
+                return true;
+            }
+            if index == 0 {
+                return false;
+            }
+            index = self.get_raw(index).parent();
+        }
+    }
+
+    /// moves the node at index together with its subtree to the end of the childs of new_parent
+    ///
+    /// #Panics
+    /// Panics if new_parent is part of the subtree of index
+    ///
+    /// #Safety
+    /// index and new_parent must be inside the buffers bounds, index must be used
+    pub(crate) unsafe fn reparent(&mut self, index: NonZeroU32, new_parent: u32) {
+        assert!(!self.is_ancestor(index.get(), new_parent), "can not move a node into its own subtree!");
+        self.detach(index);
+        self.get_raw_mut(index.get()).set_parent(new_parent);
+        self.get_raw_mut(new_parent).childs.push(index);
+    }
+
+    /// removes the node at index and its subtree from the Tree and returns them as a new Tree.
+    /// The nodes of the new Tree are allocated in pre-order.
+    ///
+    /// #Safety
+    /// index must be a used Element
+    pub(crate) unsafe fn take_subtree(&mut self, index: NonZeroU32) -> Tree<T> {
+        self.detach(index);
+        let mut stack: Vec<(NonZeroU32, u32)> = Vec::new();
+        stack.extend(self.get_raw(index.get()).childs().iter().rev().map(|child|(*child, 0)));
+        self.get_raw_mut(index.get()).childs.clear();
+        let mut tree = Tree::new(self.free(index));
+
+        while let Some((old_index, new_parent)) = stack.pop() {
+            let new_index = tree.push_child(new_parent, self.free(old_index));
+            let element = self.get_raw_mut(old_index.get());
+            stack.extend(element.childs().iter().rev().map(|child|(*child, new_index.get())));
+            element.childs.clear();
+        }
+        tree
+    }
+
+    /// makes the node at index the new root of the Tree. Since the root is always stored at index
+    /// 0, the new root and the old root swap their indices, all other nodes keep their index.
+    ///
+    /// In [`RerootMode::Undirected`] the former parent of the new root becomes its last child, the
+    /// same applies to every node along the path to the old root.
+    /// In [`RerootMode::Subtree`] all nodes outside of the subtree of index are dropped.
+    ///
+    /// #Panics
+    /// Panics if index is unused
+    pub fn reroot(&mut self, index: u32, mode: RerootMode) {
+        assert!(self.get_index(index).is_some(), "rerooted to an unused Element!");
+        if index == 0 {
+            return;
+        }
+        unsafe {
+            match mode {
+                RerootMode::Undirected => self.evert(index),
+                RerootMode::Subtree => {
+                    let node = NonZeroU32::new_unchecked(index);
+                    self.detach(node);
+                    self.free_descendants(0);
+
+                    self.buffer.swap(0, index as usize);
+                    self.get_raw_mut(0).set_parent(0);
+                    for child in self.get_raw(0).childs().to_vec() {
+                        self.get_raw_mut(child.get()).set_parent(0);
+                    }
+                    self.free(node);
+                }
+            }
+        }
+    }
+
+    /// detaches every node except the root for which predicate returns true, together with its
+    /// subtree, and returns each as a separate Tree in pre-order of their roots. Matching nodes
+    /// inside the subtree of another matching node become separate Trees as well.
+    pub fn split_off(&mut self, mut predicate: impl FnMut(Ref<T>) -> bool) -> Vec<Tree<T>> {
+        let matches: Vec<u32> = self.top().pre_order().skip(1).filter_map(|node|{
+            let index = node.index();
+            if predicate(node) { Some(index) } else { None }
+        }).collect();
+
+        // extracting the deepest nodes first leaves nested matches out of the outer Trees
+        let mut trees: Vec<Tree<T>> = matches.into_iter().rev().map(|index|unsafe {
+            self.take_subtree(NonZeroU32::new_unchecked(index))
+        }).collect();
+        trees.reverse();
+        trees
+    }
+
+    /// reverses the parent edges along the path from index to the root and swaps the Elements at
+    /// 0 and index. Child indices are written already mapped to the swapped positions, since an
+    /// index of 0 can not be stored in the childs.
+    unsafe fn evert(&mut self, index: u32) {
+        let map = |i: u32| if i == 0 { index } else if i == index { 0 } else { i };
+
+        let mut path = vec![index];
+        let mut current = index;
+        while current != 0 {
+            current = self.get_raw(current).parent();
+            path.push(current);
+        }
+        let below_root = path[path.len() - 2];
+
+        // the children outside of the path follow the new positions of their parents
+        for child in self.get_raw(index).childs().to_vec() {
+            self.get_raw_mut(child.get()).set_parent(0);
+        }
+        for child in self.get_raw(0).childs().to_vec() {
+            if child.get() != below_root {
+                self.get_raw_mut(child.get()).set_parent(index);
+            }
+        }
+
+        for pair in path.windows(2) {
+            let (child, parent) = (pair[0], pair[1]);
+            let childs = &mut self.get_raw_mut(parent).childs;
+            let position = childs.iter().position(|c|c.get() == child).expect("node is not a child of its parent!");
+            childs.remove(position);
+        }
+        for pair in path.windows(2) {
+            let (child, parent) = (pair[0], pair[1]);
+            self.get_raw_mut(child).childs.push(NonZeroU32::new_unchecked(map(parent)));
+            self.get_raw_mut(parent).set_parent(map(child));
+        }
+
+        self.buffer.swap(0, index as usize);
+        self.get_raw_mut(0).set_parent(0);
+    }
+
     /// returns the Element at index
     ///
     /// #Safety
@@ -287,9 +616,28 @@ impl<T: 'static> Tree<T> {
     }
 }
 
+impl<T: Debug + 'static> Tree<T> {
+    /// returns a Debug representation of the raw buffer including the unused Elements and the
+    /// queue of free Elements
+    pub fn debug_pool(&self) -> impl Debug + '_ {
+        DebugPool(self)
+    }
+}
+
+struct DebugPool<'a, T>(&'a Tree<T>);
+
+impl<'a, T: Debug> Debug for DebugPool<'a, T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Pool")
+            .field("next_free", &self.0.next_free)
+            .field("elements", &self.0.buffer)
+            .finish()
+    }
+}
+
 impl<T: Debug + 'static> Debug for Tree<T> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        self.buffer.fmt(f)
+        self.top().fmt(f)
     }
 }
 
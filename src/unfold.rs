@@ -0,0 +1,75 @@
+use std::collections::VecDeque;
+use crate::tree::Tree;
+
+/// The options of [`Tree::unfold_with`]
+#[derive(Clone, Debug, Default)]
+pub struct UnfoldOptions {
+    /// expands the seeds level by level instead of depth-first, this changes which nodes are
+    /// created if max_nodes is reached
+    pub breadth_first: bool,
+    /// the maximal depth of the nodes, the root has depth 0. The seeds of nodes at this depth are
+    /// expanded for their value, but their child seeds are dropped.
+    pub max_depth: Option<usize>,
+    /// the maximal number of nodes, the remaining seeds are dropped
+    pub max_nodes: Option<usize>,
+}
+
+impl<T: 'static> Tree<T> {
+    /// grows a Tree from a seed. expand turns a seed into the value of its node and the seeds of
+    /// its children, which are expanded depth-first. The children are kept in the order of their
+    /// seeds.
+    pub fn unfold<S, I: IntoIterator<Item = S>>(seed: S, expand: impl FnMut(S) -> (T, I)) -> Self {
+        Tree::unfold_with(seed, &UnfoldOptions::default(), expand)
+    }
+
+    /// grows a Tree from a seed like [`Tree::unfold`], but the seeds are expanded level by level
+    pub fn unfold_breadth_first<S, I: IntoIterator<Item = S>>(seed: S, expand: impl FnMut(S) -> (T, I)) -> Self {
+        Tree::unfold_with(seed, &UnfoldOptions{ breadth_first: true, ..UnfoldOptions::default() }, expand)
+    }
+
+    /// grows a Tree from a seed like [`Tree::unfold`] with the order and limits of options. The
+    /// iterators of child seeds are consumed lazily, so they are never advanced behind the limits.
+    pub fn unfold_with<S, I: IntoIterator<Item = S>>(seed: S, options: &UnfoldOptions, mut expand: impl FnMut(S) -> (T, I)) -> Self {
+        let (value, seeds) = expand(seed);
+        let mut tree = Tree::new(value);
+        let max_nodes = options.max_nodes.unwrap_or(usize::MAX);
+        let expands = |depth: usize|options.max_depth.is_none_or(|max|depth < max);
+        if max_nodes <= 1 || !expands(0) {
+            return tree;
+        }
+
+        let mut count = 1;
+        // the nodes with the seeds of their remaining children and their depth
+        let mut pending = VecDeque::new();
+        pending.push_back((0, 0, seeds.into_iter()));
+        loop {
+            let next = if options.breadth_first { pending.front_mut() } else { pending.back_mut() };
+            let (parent, depth, seeds) = match next {
+                Some(next) => next,
+                None => return tree,
+            };
+            let (parent, depth) = (*parent, *depth);
+            let seed = match seeds.next() {
+                Some(seed) => seed,
+                None => {
+                    if options.breadth_first {
+                        pending.pop_front();
+                    } else {
+                        pending.pop_back();
+                    }
+                    continue;
+                }
+            };
+
+            let (value, seeds) = expand(seed);
+            let index = unsafe {tree.push_child(parent, value)};
+            count += 1;
+            if count == max_nodes {
+                return tree;
+            }
+            if expands(depth + 1) {
+                pending.push_back((index.get(), depth + 1, seeds.into_iter()));
+            }
+        }
+    }
+}
@@ -0,0 +1,126 @@
+use crate::tree::Tree;
+use crate::reference::{Ref, TreeRef};
+use crate::ref_mut::{RefMut, TreeRefMut};
+
+/// The result of the hooks of a visitor, which controls how the traversal continues
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum VisitControl {
+    Continue,
+    /// the children of the entered node are not visited, leave is still called for it. Returned
+    /// by leave or visit_leaf it is the same as Continue.
+    SkipChildren,
+    /// ends the traversal immediately, no further hooks are called
+    Stop,
+}
+
+/// A visitor of the nodes of a Tree, see [`walk`]. Every hook continues by default.
+pub trait Visitor<T> {
+    /// called for a node with children before its children are visited
+    fn enter(&mut self, _node: Ref<T>) -> VisitControl {
+        VisitControl::Continue
+    }
+
+    /// called for a node with children after its children were visited
+    fn leave(&mut self, _node: Ref<T>) -> VisitControl {
+        VisitControl::Continue
+    }
+
+    /// called for a node without children instead of enter and leave
+    fn visit_leaf(&mut self, _node: Ref<T>) -> VisitControl {
+        VisitControl::Continue
+    }
+}
+
+/// A visitor which can change the values of the nodes, see [`walk_mut`]
+pub trait VisitorMut<T> {
+    /// called for a node with children before its children are visited
+    fn enter(&mut self, _node: RefMut<T>) -> VisitControl {
+        VisitControl::Continue
+    }
+
+    /// called for a node with children after its children were visited
+    fn leave(&mut self, _node: RefMut<T>) -> VisitControl {
+        VisitControl::Continue
+    }
+
+    /// called for a node without children instead of enter and leave
+    fn visit_leaf(&mut self, _node: RefMut<T>) -> VisitControl {
+        VisitControl::Continue
+    }
+}
+
+/// walks the subtree of node depth-first without recursion and calls the hooks of visitor.
+/// Returns Stop if a hook stopped the traversal and Continue otherwise.
+pub fn walk<R: TreeRef>(node: &R, visitor: &mut impl Visitor<R::Type>) -> VisitControl {
+    let node = node.get_ref();
+    let tree = node.tree();
+    traverse(tree, node.index(), |index, hook|{
+        let node = unsafe {tree.get_unchecked(index)};
+        match hook {
+            Hook::Enter => visitor.enter(node),
+            Hook::Leave => visitor.leave(node),
+            Hook::Leaf => visitor.visit_leaf(node),
+        }
+    })
+}
+
+/// walks the subtree of node like [`walk`], the hooks of visitor get a RefMut to every node
+pub fn walk_mut<R: TreeRefMut>(node: &mut R, visitor: &mut impl VisitorMut<R::Type>) -> VisitControl {
+    let node = node.get_mut();
+    let buffer = node.buffer;
+    traverse(unsafe {&*buffer}, node.index, |index, hook|{
+        let node = unsafe {RefMut::create(index, buffer)};
+        match hook {
+            Hook::Enter => visitor.enter(node),
+            Hook::Leave => visitor.leave(node),
+            Hook::Leaf => visitor.visit_leaf(node),
+        }
+    })
+}
+
+enum Hook {
+    Enter,
+    Leave,
+    Leaf,
+}
+
+/// calls hook for every node in the subtree of index in the order of the visitor hooks. The
+/// childs of a node are read after its hook returned, so hooks must not change the structure.
+fn traverse<T: 'static>(tree: *const Tree<T>, index: u32, mut hook: impl FnMut(u32, Hook) -> VisitControl) -> VisitControl {
+    // the entered nodes and the position of their next child
+    let mut stack = Vec::new();
+    let mut next = Some(index);
+    loop {
+        if let Some(index) = next.take() {
+            let leaf = unsafe {(*tree).get_raw(index)}.childs().is_empty();
+            match hook(index, if leaf { Hook::Leaf } else { Hook::Enter }) {
+                VisitControl::Stop => return VisitControl::Stop,
+                VisitControl::SkipChildren if !leaf => {
+                    if hook(index, Hook::Leave) == VisitControl::Stop {
+                        return VisitControl::Stop;
+                    }
+                }
+                _ if !leaf => stack.push((index, 0)),
+                _ => {}
+            }
+        }
+
+        let (index, position) = match stack.last_mut() {
+            Some(top) => top,
+            None => return VisitControl::Continue,
+        };
+        match unsafe {(*tree).get_raw(*index)}.childs().get(*position) {
+            Some(child) => {
+                *position += 1;
+                next = Some(child.get());
+            }
+            None => {
+                let index = *index;
+                stack.pop();
+                if hook(index, Hook::Leave) == VisitControl::Stop {
+                    return VisitControl::Stop;
+                }
+            }
+        }
+    }
+}